@@ -1,4 +1,4 @@
-use clap::{Parser, ValueEnum};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 
 #[derive(ValueEnum, Clone, Copy, Debug)]
 pub enum PartValues {
@@ -9,20 +9,67 @@ pub enum PartValues {
 #[deny(missing_docs)]
 #[derive(Parser, Debug)]
 #[clap(version, author, about, long_about = None)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct Args {
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+
     /// AoC event year
-    #[clap(value_parser)]
-    pub year: u16,
+    #[clap(required = true, value_parser)]
+    pub year: Option<u16>,
 
     /// Day of puzzle
-    #[clap(value_parser)]
-    pub day: u8,
+    #[clap(required = true, value_parser)]
+    pub day: Option<u8>,
 
     /// Part of puzzle
-    #[clap(value_parser)]
-    pub part: PartValues,
+    #[clap(required = true, value_parser)]
+    pub part: Option<PartValues>,
 
     /// Run repetitions for benchmarking
     #[clap(short='n', long, default_value_t = 1, value_name = "N", value_parser)]
     pub iterations: u32,
+
+    /// Worker threads for solvers with independent work items with the `parallel` feature, 0 for all cores
+    #[clap(long, default_value_t = 0, value_name = "N", value_parser)]
+    pub threads: usize,
+
+    /// Print debug output of solvers to stderr, repeat for more detail
+    #[clap(short, long, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Only print debug output of given modules, e.g. y2021::d19
+    #[clap(long, value_name = "MODULE", value_delimiter = ',', value_parser)]
+    pub trace_filter: Vec<String>,
+
+    /// Render grid simulations in the terminal
+    #[clap(long, value_parser)]
+    pub visualize: bool,
+
+    /// Export grid simulation frames as animated GIF (*.gif) or PNG files in a directory
+    #[clap(long, value_name = "PATH", value_parser)]
+    pub export_frames: Option<std::path::PathBuf>,
+
+    /// Export graphs of supporting puzzles in Graphviz DOT format
+    #[clap(long, value_name = "FILE", value_parser)]
+    pub export_dot: Option<std::path::PathBuf>,
+
+    /// Frame rate of visualisation
+    #[clap(long, default_value_t = 10, value_name = "FPS", value_parser)]
+    pub fps: u32,
+}
+
+#[deny(missing_docs)]
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Print the program of a puzzle input as annotated pseudo-code
+    Disasm {
+        /// AoC event year
+        #[clap(value_parser)]
+        year: u16,
+
+        /// Day of puzzle
+        #[clap(value_parser)]
+        day: u8,
+    },
 }
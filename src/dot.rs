@@ -0,0 +1,253 @@
+//! Graphviz DOT export of puzzle graphs
+//!
+//! Solvers describe their parsed graph as a [`Graph`] and hand it to
+//! [`export`], which only builds it when an output file has been installed.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Write as _},
+    fs,
+    io::Write as _,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static OUTPUT: Mutex<Option<Output>> = Mutex::new(None);
+
+struct Output {
+    path: PathBuf,
+    graphs: usize,
+}
+
+/// Shapes assigned to node kinds in order of first appearance
+const SHAPES: [&str; 6] = ["ellipse", "box", "diamond", "hexagon", "octagon", "triangle"];
+/// Fill colours assigned to node kinds in order of first appearance
+const COLOURS: [&str; 6] = ["#e0e0e0", "#a6cee3", "#b2df8a", "#fdbf6f", "#fb9a99", "#cab2d6"];
+
+/// Description of a graph with labelled nodes of different kinds
+#[derive(Debug)]
+pub struct Graph {
+    name: String,
+    directed: bool,
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    index: HashMap<String, usize>,
+}
+
+#[derive(Debug)]
+pub struct Node {
+    id: String,
+    label: Option<String>,
+    kind: Option<&'static str>,
+}
+
+#[derive(Debug)]
+pub struct Edge {
+    from: usize,
+    to: usize,
+    label: Option<String>,
+}
+
+impl Graph {
+    pub fn directed(name: &str) -> Self {
+        Self::new(name, true)
+    }
+
+    pub fn undirected(name: &str) -> Self {
+        Self::new(name, false)
+    }
+
+    fn new(name: &str, directed: bool) -> Self {
+        Self {
+            name: name.to_owned(),
+            directed,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    /// Returns the node with the given id, adding it if necessary
+    pub fn node(&mut self, id: &str) -> &mut Node {
+        let idx = self.node_index(id);
+        &mut self.nodes[idx]
+    }
+
+    /// Adds an edge, implicitly adding missing nodes
+    pub fn edge(&mut self, from: &str, to: &str) -> &mut Edge {
+        let from = self.node_index(from);
+        let to = self.node_index(to);
+        self.edges.push(Edge {
+            from,
+            to,
+            label: None,
+        });
+        self.edges.last_mut().unwrap()
+    }
+
+    fn node_index(&mut self, id: &str) -> usize {
+        if let Some(&idx) = self.index.get(id) {
+            return idx;
+        }
+        self.index.insert(id.to_owned(), self.nodes.len());
+        self.nodes.push(Node {
+            id: id.to_owned(),
+            label: None,
+            kind: None,
+        });
+        self.nodes.len() - 1
+    }
+}
+
+impl Node {
+    pub fn label(&mut self, label: impl Into<String>) -> &mut Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Sets the kind of node, which determines its shape and colour
+    pub fn kind(&mut self, kind: &'static str) -> &mut Self {
+        self.kind = Some(kind);
+        self
+    }
+}
+
+impl Edge {
+    pub fn label(&mut self, label: impl Into<String>) -> &mut Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+impl fmt::Display for Graph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (keyword, arrow) = if self.directed { ("digraph", "->") } else { ("graph", "--") };
+        writeln!(f, "{keyword} {} {{", quote(&self.name))?;
+
+        let mut kinds = Vec::new();
+        for node in &self.nodes {
+            let mut attrs = String::new();
+            if let Some(label) = &node.label {
+                write!(attrs, "label={}, ", quote(label))?;
+            }
+            if let Some(kind) = node.kind {
+                let i = kinds.iter().position(|&k| k == kind).unwrap_or_else(|| {
+                    kinds.push(kind);
+                    kinds.len() - 1
+                });
+                write!(
+                    attrs,
+                    "class={}, shape={}, style=filled, fillcolor=\"{}\", ",
+                    quote(kind),
+                    SHAPES[i % SHAPES.len()],
+                    COLOURS[i % COLOURS.len()]
+                )?;
+            }
+            match attrs.strip_suffix(", ") {
+                Some(attrs) => writeln!(f, "  {} [{attrs}];", quote(&node.id))?,
+                None => writeln!(f, "  {};", quote(&node.id))?,
+            }
+        }
+
+        for edge in &self.edges {
+            let (from, to) = (&self.nodes[edge.from].id, &self.nodes[edge.to].id);
+            write!(f, "  {} {arrow} {}", quote(from), quote(to))?;
+            match &edge.label {
+                Some(label) => writeln!(f, " [label={}];", quote(label))?,
+                None => writeln!(f, ";")?,
+            }
+        }
+
+        writeln!(f, "}}")
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Sets the file that subsequently exported graphs are written to
+pub fn install(path: impl Into<PathBuf>) {
+    *OUTPUT.lock().unwrap() = Some(Output {
+        path: path.into(),
+        graphs: 0,
+    });
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Stops exporting graphs
+pub fn finish() {
+    ENABLED.store(false, Ordering::Relaxed);
+    if let Some(output) = OUTPUT.lock().unwrap().take()
+        && output.graphs > 0
+    {
+        println!("{} graphs exported to {}.", output.graphs, output.path.display());
+    }
+}
+
+/// Whether graphs should be exported
+#[inline]
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Writes the graph built by `f` to the output file, if any
+///
+/// Multiple graphs of a run are appended to the same file.
+#[inline]
+pub fn export<F: FnOnce() -> Graph>(f: F) {
+    if enabled() {
+        write_graph(&f());
+    }
+}
+
+#[cold]
+fn write_graph(graph: &Graph) {
+    let mut output = OUTPUT.lock().unwrap();
+    let Some(output) = output.as_mut() else {
+        return;
+    };
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(output.graphs > 0)
+        .truncate(output.graphs == 0)
+        .open(&output.path)
+        .expect("failed to open DOT file");
+    write!(file, "{graph}").expect("failed to write DOT file");
+    output.graphs += 1;
+}
+
+#[test]
+pub fn test() {
+    let mut graph = Graph::directed("circuit");
+    graph.node("x00").kind("input");
+    graph.node("AND z00").kind("gate").label("AND");
+    graph.edge("x00", "AND z00").label("in1");
+    graph.edge("y\"00", "AND z00");
+    graph.edge("AND z00", "z00");
+    assert_eq!(
+        graph.to_string(),
+        [
+            "digraph \"circuit\" {",
+            "  \"x00\" [class=\"input\", shape=ellipse, style=filled, fillcolor=\"#e0e0e0\"];",
+            "  \"AND z00\" [label=\"AND\", class=\"gate\", shape=box, style=filled, fillcolor=\"#a6cee3\"];",
+            "  \"y\\\"00\";",
+            "  \"z00\";",
+            "  \"x00\" -> \"AND z00\" [label=\"in1\"];",
+            "  \"y\\\"00\" -> \"AND z00\";",
+            "  \"AND z00\" -> \"z00\";",
+            "}",
+            "",
+        ]
+        .join("\n")
+    );
+
+    let mut graph = Graph::undirected("lan");
+    graph.edge("ka", "co");
+    assert!(graph.to_string().contains("\"ka\" -- \"co\";"));
+}
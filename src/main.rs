@@ -2,7 +2,11 @@ use clap::Parser;
 use std::{time::{Instant, Duration}, path::Path};
 
 mod args;
+mod dot;
+mod trace;
 mod utils;
+mod visual;
+mod vm;
 mod y2020;
 mod y2021;
 mod y2022;
@@ -10,33 +14,53 @@ mod y2023;
 mod y2024;
 mod y2025;
 
-use crate::args::Args;
+use crate::args::{Args, Command};
 
 type SolveFunc = fn(&Vec<&str>) -> String;
 
 fn main() {
     let args = Args::parse();
-    println!("Solving AoC {}/{:02}/{:?}...", args.year, args.day, args.part);
-
-    let solve: SolveFunc = match args.year {
-        2020 => y2020::solver(args.day, args.part),
-        2021 => y2021::solver(args.day, args.part),
-        2022 => y2022::solver(args.day, args.part),
-        2023 => y2023::solver(args.day, args.part),
-        2024 => y2024::solver(args.day, args.part),
-        2025 => y2025::solver(args.day, args.part),
-        _ => unimplemented!("Unsupported year: {}", args.year),
+    trace::init(args.verbose, args.trace_filter.clone());
+    utils::parallel::set_threads(args.threads);
+    if let Some(Command::Disasm { year, day }) = args.command {
+        return disassemble(year, day);
+    }
+
+    // required unless a subcommand is given
+    let (year, day, part) = (args.year.unwrap(), args.day.unwrap(), args.part.unwrap());
+    println!("Solving AoC {}/{:02}/{:?}...", year, day, part);
+
+    let solve: SolveFunc = match year {
+        2020 => y2020::solver(day, part),
+        2021 => y2021::solver(day, part),
+        2022 => y2022::solver(day, part),
+        2023 => y2023::solver(day, part),
+        2024 => y2024::solver(day, part),
+        2025 => y2025::solver(day, part),
+        _ => unimplemented!("Unsupported year: {}", year),
     };
 
-    let input_str = get_input(args.year, args.day);
+    let input_str = get_input(year, day);
     let input = input_str.lines().collect::<Vec<_>>();
     println!("Input: {} lines @ {:.1} kB", input.len(), input_str.len() / 1024);
-    
+
+    if args.visualize {
+        visual::install(Box::new(visual::Terminal::new(args.fps)));
+    }
+    if let Some(path) = &args.export_frames {
+        visual::install(Box::new(visual::Export::new(path, args.fps)));
+    }
+    if let Some(path) = &args.export_dot {
+        dot::install(path);
+    }
+
     let total: Duration = (0..args.iterations).map(|i| {
         let start = Instant::now();
         let solution = solve(&input);
         let duration = start.elapsed();
         if i == 0 {
+            visual::finish();
+            dot::finish();
             if solution.is_empty() {
                 println!("No solution output.");
             } else {
@@ -52,6 +76,21 @@ fn main() {
     }
 }
 
+fn disassemble(year: u16, day: u8) {
+    let disassemble: SolveFunc = match year {
+        2020 => y2020::disassembler(day),
+        2021 => y2021::disassembler(day),
+        2022 => y2022::disassembler(day),
+        2024 => y2024::disassembler(day),
+        _ => None,
+    }
+    .unwrap_or_else(|| unimplemented!("No disassembler for {year}/{day:02}"));
+
+    let input_str = get_input(year, day);
+    let input = input_str.lines().collect::<Vec<_>>();
+    print!("{}", disassemble(&input));
+}
+
 fn get_input(year: u16, day: u8) -> String {
     let cache_path = format!("cache/y{year}/d{day}.txt");
     let cache_path = Path::new(&cache_path);
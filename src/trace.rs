@@ -0,0 +1,94 @@
+//! Leveled debug output of solvers
+//!
+//! Messages are written to stderr through [`debug!`] (`-v`) and [`trace!`]
+//! (`-vv`), optionally restricted to modules given with `--trace-filter`.
+//! Format arguments are only evaluated for enabled messages, so disabled call
+//! sites cost a single atomic load.
+
+use std::sync::{
+    atomic::{AtomicU8, Ordering},
+    OnceLock,
+};
+
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+static FILTERS: OnceLock<Vec<String>> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Level {
+    Debug = 1,
+    Trace = 2,
+}
+
+/// Enables messages up to verbosity `level` (0 = silent) of modules starting
+/// with any of the `filters`, e.g. `y2021` or `y2021::d19`
+pub fn init(level: u8, filters: Vec<String>) {
+    let _ = FILTERS.set(filters);
+    LEVEL.store(level, Ordering::Relaxed);
+}
+
+/// Whether messages of `level` are enabled for `module`
+#[inline]
+pub fn enabled(level: Level, module: &str) -> bool {
+    LEVEL.load(Ordering::Relaxed) >= level as u8 && module_enabled(module)
+}
+
+#[cold]
+fn module_enabled(module: &str) -> bool {
+    let module = target(module);
+    match FILTERS.get() {
+        Some(filters) if !filters.is_empty() => filters.iter().any(|f| {
+            module
+                .strip_prefix(f.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+        }),
+        _ => true,
+    }
+}
+
+/// Module path without the crate name
+pub fn target(module: &str) -> &str {
+    module.split_once("::").map_or(module, |(_, path)| path)
+}
+
+/// Writes a message at the given level, if enabled for the calling module
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::trace::enabled($level, module_path!()) {
+            eprintln!("[{}] {}", $crate::trace::target(module_path!()), format_args!($($arg)+));
+        }
+    };
+}
+
+/// Writes a message shown with `-v`
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        $crate::trace::log!($crate::trace::Level::Debug, $($arg)+)
+    };
+}
+
+/// Writes a message shown with `-vv`
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        $crate::trace::log!($crate::trace::Level::Trace, $($arg)+)
+    };
+}
+
+pub(crate) use {debug, log, trace};
+
+#[test]
+pub fn test() {
+    assert_eq!(target("advent_of_code::y2021::d19"), "y2021::d19");
+    assert!(!enabled(Level::Debug, module_path!()));
+
+    // arguments are not evaluated when disabled
+    let mut evaluated = false;
+    debug!("{}", {
+        evaluated = true;
+        0
+    });
+    trace!("{}", {
+        evaluated = true;
+        0
+    });
+    assert!(!evaluated);
+}
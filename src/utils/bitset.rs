@@ -0,0 +1,192 @@
+//! Dense sets of small integers and 2D points packed into machine words
+
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+/// Set of `usize` values stored as one bit each, grows as values are inserted
+#[derive(Clone, Default)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Empty set with room for values below `bits` without reallocating
+    pub fn with_capacity(bits: usize) -> Self {
+        Self {
+            words: vec![0; bits.div_ceil(64)],
+        }
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.words
+            .get(value / 64)
+            .is_some_and(|word| word >> (value % 64) & 1 == 1)
+    }
+
+    /// Adds a value, false if it was already present
+    pub fn insert(&mut self, value: usize) -> bool {
+        let idx = value / 64;
+        if idx >= self.words.len() {
+            self.words.resize(idx + 1, 0);
+        }
+        let mask = 1 << (value % 64);
+        let added = self.words[idx] & mask == 0;
+        self.words[idx] |= mask;
+        added
+    }
+
+    /// Removes a value, false if it wasn't present
+    pub fn remove(&mut self, value: usize) -> bool {
+        let Some(word) = self.words.get_mut(value / 64) else {
+            return false;
+        };
+        let mask = 1 << (value % 64);
+        let removed = *word & mask != 0;
+        *word &= !mask;
+        removed
+    }
+
+    /// number of values
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// values in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(idx, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    idx * 64 + bit
+                })
+            })
+        })
+    }
+
+    /// words up to the last one with a value, independent of capacity
+    fn used_words(&self) -> &[u64] {
+        let len = self.words.iter().rposition(|&word| word != 0).map_or(0, |idx| idx + 1);
+        &self.words[..len]
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+/// Sets are equal if they hold the same values, regardless of capacity
+impl PartialEq for BitSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.used_words() == other.used_words()
+    }
+}
+
+impl Eq for BitSet {}
+
+impl Hash for BitSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.used_words().hash(state);
+    }
+}
+
+impl fmt::Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Set of points in a fixed `width` x `height` area, one bit per point
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    bits: BitSet,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            bits: BitSet::with_capacity(width * height),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// whether the point is set, false outside the grid
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.bits.contains(y * self.width + x)
+    }
+
+    /// Sets a point inside the grid, false if it was already set
+    pub fn insert(&mut self, x: usize, y: usize) -> bool {
+        assert!(x < self.width && y < self.height, "point outside grid");
+        self.bits.insert(y * self.width + x)
+    }
+
+    /// Clears a point, false if it wasn't set
+    pub fn remove(&mut self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.bits.remove(y * self.width + x)
+    }
+}
+
+#[test]
+pub fn test() {
+    let mut set = BitSet::with_capacity(10);
+    assert_eq!(set.len(), 0);
+    assert!(set.insert(3));
+    assert!(!set.insert(3));
+    assert!(set.insert(64));
+    assert!(set.insert(200));
+    assert!(set.contains(64) && !set.contains(65) && !set.contains(100000));
+    assert_eq!(set.len(), 3);
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![3, 64, 200]);
+    assert!(set.remove(64));
+    assert!(!set.remove(64) && !set.remove(100000));
+    assert_eq!(format!("{set:?}"), "{3, 200}");
+
+    // equality and hashing ignore capacity
+    assert!(set.remove(200));
+    assert_eq!(set, [3].into_iter().collect::<BitSet>());
+    assert_eq!(BitSet::new(), BitSet::with_capacity(64));
+    set.remove(3);
+    assert_eq!(set, BitSet::new());
+    assert_ne!(set, [0].into_iter().collect::<BitSet>());
+    assert_eq!([set, BitSet::new()].into_iter().collect::<std::collections::HashSet<_>>().len(), 1);
+
+    let mut grid = BitGrid::new(5, 3);
+    assert!(grid.insert(4, 0));
+    assert!(grid.insert(0, 2));
+    assert!(!grid.insert(4, 0));
+    assert!(grid.contains(4, 0) && !grid.contains(0, 1));
+    assert!(!grid.contains(5, 0) && !grid.contains(0, 3));
+    assert!(grid.remove(4, 0) && !grid.remove(7, 7));
+    assert!(!grid.contains(4, 0) && grid.contains(0, 2));
+}
@@ -0,0 +1,196 @@
+//! Axis-aligned boxes of integer points in N dimensions
+
+use super::interval::Interval;
+
+/// Box of integer points, the product of one half-open interval per axis
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cuboid<const N: usize> {
+    pub ranges: [Interval<i64>; N],
+}
+
+impl<const N: usize> Cuboid<N> {
+    pub fn new(ranges: [Interval<i64>; N]) -> Self {
+        Self { ranges }
+    }
+
+    /// Box from corner `min` to corner `max`, both included
+    pub fn inclusive(min: [i64; N], max: [i64; N]) -> Self {
+        Self::new(std::array::from_fn(|i| Interval::inclusive(min[i], max[i])))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.iter().any(Interval::is_empty)
+    }
+
+    /// number of contained points
+    #[cfg(test)]
+    pub fn volume(&self) -> u64 {
+        self.ranges.iter().map(|range| range.len() as u64).product()
+    }
+
+    #[cfg(test)]
+    pub fn contains(&self, point: [i64; N]) -> bool {
+        self.ranges.iter().zip(point).all(|(range, value)| range.contains(value))
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self::new(std::array::from_fn(|i| self.ranges[i].intersection(&other.ranges[i])))
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// Box moved by `offset`
+    pub fn translate(&self, offset: [i64; N]) -> Self {
+        Self::new(std::array::from_fn(|i| {
+            Interval::new(self.ranges[i].start + offset[i], self.ranges[i].end + offset[i])
+        }))
+    }
+
+    /// Disjoint boxes covering the points not in `other`, at most two per axis,
+    /// the reference [`count_on`] is checked against
+    #[cfg(test)]
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        if !self.intersects(other) {
+            return vec![*self];
+        }
+        // slabs below and above `other` along each axis, shrinking the rest to the overlap
+        let mut pieces = Vec::new();
+        let mut rest = *self;
+        for axis in 0..N {
+            let (below, inside) = rest.ranges[axis].split_at(other.ranges[axis].start);
+            let (inside, above) = inside.split_at(other.ranges[axis].end);
+            for slab in [below, above] {
+                if !slab.is_empty() {
+                    let mut piece = rest;
+                    piece.ranges[axis] = slab;
+                    pieces.push(piece);
+                }
+            }
+            rest.ranges[axis] = inside;
+        }
+        pieces
+    }
+}
+
+/// Number of points switched on after applying all `(cuboid, on)` steps in order,
+/// starting with all points off
+///
+/// Sweeps over the compressed coordinates of one axis after the other, so the
+/// running time depends on the number of steps and not on their sizes.
+pub fn count_on<const N: usize>(steps: &[(Cuboid<N>, bool)]) -> u64 {
+    let steps = steps
+        .iter()
+        .filter(|(cuboid, _)| !cuboid.is_empty())
+        .map(|(cuboid, on)| (cuboid.ranges.as_slice(), *on))
+        .collect::<Vec<_>>();
+    count_on_ranges(&steps)
+}
+
+fn count_on_ranges(steps: &[(&[Interval<i64>], bool)]) -> u64 {
+    let Some(&(ranges, _)) = steps.first() else {
+        return 0;
+    };
+    if ranges.is_empty() {
+        // the point is in the state of the last step
+        return steps.last().unwrap().1 as u64;
+    }
+
+    let mut bounds = steps
+        .iter()
+        .flat_map(|(ranges, _)| [ranges[0].start, ranges[0].end])
+        .collect::<Vec<_>>();
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    // steps by start along the swept axis, each becomes active at its start until its end
+    let mut starts = (0..steps.len()).collect::<Vec<_>>();
+    starts.sort_unstable_by_key(|&idx| steps[idx].0[0].start);
+    let mut starts = starts.into_iter().peekable();
+    let mut active = Vec::<usize>::with_capacity(steps.len());
+
+    let mut count = 0;
+    let mut covering = Vec::with_capacity(steps.len());
+    for slab in bounds.windows(2) {
+        active.retain(|&idx| steps[idx].0[0].end > slab[0]);
+        while let Some(idx) = starts.next_if(|&idx| steps[idx].0[0].start <= slab[0]) {
+            let pos = active.partition_point(|&other| other < idx);
+            active.insert(pos, idx);
+        }
+        if ranges.len() == 1 {
+            // last axis, the slab is in the state of the last covering step
+            if active.last().is_some_and(|&idx| steps[idx].1) {
+                count += (slab[1] - slab[0]) as u64;
+            }
+            continue;
+        }
+        if !active.iter().any(|&idx| steps[idx].1) {
+            continue;
+        }
+        // steps covering the slab, in order, without the swept axis
+        covering.clear();
+        covering.extend(active.iter().map(|&idx| (&steps[idx].0[1..], steps[idx].1)));
+        count += count_on_ranges(&covering) * (slab[1] - slab[0]) as u64;
+    }
+    count
+}
+
+#[test]
+pub fn test() {
+    let a = Cuboid::inclusive([10, 10, 10], [12, 12, 12]);
+    let b = Cuboid::inclusive([11, 11, 11], [13, 13, 13]);
+    assert_eq!(a.volume(), 27);
+    assert_eq!(a.intersection(&b), Cuboid::inclusive([11, 11, 11], [12, 12, 12]));
+    assert!(a.contains([12, 10, 11]) && !a.contains([13, 10, 11]));
+    assert!(!a.intersects(&a.translate([3, 0, 0])));
+    assert!(Cuboid::inclusive([0, 0], [-1, 5]).is_empty());
+
+    let pieces = a.subtract(&b);
+    assert_eq!(pieces.iter().map(Cuboid::volume).sum::<u64>(), 27 - 8);
+    assert_eq!(a.subtract(&a), Vec::new());
+    assert_eq!(a.subtract(&a.translate([5, 5, 5])), vec![a]);
+    // hole in the middle
+    let ring = Cuboid::inclusive([0, 0], [4, 4]).subtract(&Cuboid::inclusive([1, 1], [3, 3]));
+    assert_eq!(ring.len(), 4);
+    assert_eq!(ring.iter().map(Cuboid::volume).sum::<u64>(), 16);
+
+    let steps = [
+        (a, true),
+        (b, true),
+        (Cuboid::inclusive([9, 9, 9], [11, 11, 11]), false),
+        (Cuboid::inclusive([10, 10, 10], [10, 10, 10]), true),
+    ];
+    assert_eq!(count_on(&steps), 39);
+    assert_eq!(count_on::<3>(&[]), 0);
+
+    // random steps agree with disjoint pieces and single points
+    let mut rng = crate::utils::Rng::new(45);
+    for _ in 0..50 {
+        let steps = (0..10)
+            .map(|_| {
+                let min: [i64; 3] = std::array::from_fn(|_| (rng.next_u64() % 12) as i64);
+                let max = min.map(|c| c + (rng.next_u64() % 6) as i64 - 1);
+                (Cuboid::inclusive(min, max), !rng.next_u64().is_multiple_of(3))
+            })
+            .collect::<Vec<_>>();
+        let mut pieces = Vec::<Cuboid<3>>::new();
+        for (cuboid, on) in &steps {
+            pieces = pieces.iter().flat_map(|piece| piece.subtract(cuboid)).collect();
+            if *on && !cuboid.is_empty() {
+                pieces.push(*cuboid);
+            }
+        }
+        let mut points = 0;
+        for x in 0..20 {
+            for y in 0..20 {
+                for z in 0..20 {
+                    let last = steps.iter().rev().find(|(cuboid, _)| cuboid.contains([x, y, z]));
+                    points += last.is_some_and(|(_, on)| *on) as u64;
+                }
+            }
+        }
+        assert_eq!(pieces.iter().map(Cuboid::volume).sum::<u64>(), points);
+        assert_eq!(count_on(&steps), points);
+    }
+}
@@ -0,0 +1,400 @@
+//! Graphs over interned node labels and the algorithms puzzles keep asking for
+//!
+//! Nodes are numbered in order of first appearance, algorithms work on these
+//! indices and [`Graph::label`] maps them back.
+
+use std::{
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+/// Adjacency lists of weighted edges between labelled nodes
+#[derive(Clone, Debug)]
+pub struct Graph<L> {
+    directed: bool,
+    labels: Vec<L>,
+    index: HashMap<L, usize>,
+    adj: Vec<Vec<(usize, u64)>>,
+}
+
+impl<L: Copy + Eq + Hash> Graph<L> {
+    pub fn directed() -> Self {
+        Self::new(true)
+    }
+
+    pub fn undirected() -> Self {
+        Self::new(false)
+    }
+
+    fn new(directed: bool) -> Self {
+        Self {
+            directed,
+            labels: Vec::new(),
+            index: HashMap::new(),
+            adj: Vec::new(),
+        }
+    }
+
+    /// number of nodes
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Returns the index of the node with the given label, adding it if necessary
+    pub fn node(&mut self, label: L) -> usize {
+        if let Some(&idx) = self.index.get(&label) {
+            return idx;
+        }
+        let idx = self.labels.len();
+        self.labels.push(label);
+        self.index.insert(label, idx);
+        self.adj.push(Vec::new());
+        idx
+    }
+
+    /// index of an existing node
+    pub fn index(&self, label: L) -> Option<usize> {
+        self.index.get(&label).copied()
+    }
+
+    pub fn label(&self, idx: usize) -> L {
+        self.labels[idx]
+    }
+
+    /// Adds an edge of weight 1, implicitly adding missing nodes
+    pub fn edge(&mut self, from: L, to: L) {
+        self.weighted_edge(from, to, 1);
+    }
+
+    /// Adds an edge, implicitly adding missing nodes
+    pub fn weighted_edge(&mut self, from: L, to: L, weight: u64) {
+        let (from, to) = (self.node(from), self.node(to));
+        self.adj[from].push((to, weight));
+        if !self.directed && from != to {
+            self.adj[to].push((from, weight));
+        }
+    }
+
+    pub fn neighbors(&self, idx: usize) -> impl Iterator<Item = usize> + '_ {
+        self.adj[idx].iter().map(|&(to, _)| to)
+    }
+
+    pub fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.neighbors(from).any(|idx| idx == to)
+    }
+
+    /// Stoer–Wagner minimum cut of an undirected graph,
+    /// returns the cut weight and the sorted nodes on one side
+    pub fn min_cut(&self) -> Option<(u64, Vec<usize>)> {
+        assert!(!self.directed, "min cut needs an undirected graph");
+        let n = self.len();
+        if n < 2 {
+            return None;
+        }
+
+        // merged nodes keep summed edge weights to their neighbours
+        let mut adj = vec![HashMap::<usize, u64>::new(); n];
+        for (from, edges) in self.adj.iter().enumerate() {
+            for &(to, weight) in edges.iter().filter(|&&(to, _)| to != from) {
+                *adj[from].entry(to).or_default() += weight;
+            }
+        }
+        let mut members = (0..n).map(|idx| vec![idx]).collect::<Vec<_>>();
+        let mut active = (0..n).collect::<Vec<_>>();
+        let mut best: Option<(u64, Vec<usize>)> = None;
+
+        while active.len() > 1 {
+            // maximum adjacency order, stale heap entries are skipped
+            let mut weights = vec![0; n];
+            let mut added = vec![false; n];
+            let mut heap = active.iter().map(|&idx| (0, idx)).collect::<BinaryHeap<_>>();
+            let (mut prev, mut last, mut cut) = (usize::MAX, usize::MAX, 0);
+            while let Some((weight, idx)) = heap.pop() {
+                if added[idx] || weight != weights[idx] {
+                    continue;
+                }
+                added[idx] = true;
+                (prev, last, cut) = (last, idx, weight);
+                for (&to, &weight) in &adj[idx] {
+                    if !added[to] {
+                        weights[to] += weight;
+                        heap.push((weights[to], to));
+                    }
+                }
+            }
+
+            if best.as_ref().is_none_or(|(weight, _)| cut < *weight) {
+                best = Some((cut, members[last].clone()));
+            }
+
+            // merge last node into the one added before it
+            for (to, weight) in std::mem::take(&mut adj[last]) {
+                adj[to].remove(&last);
+                if to != prev {
+                    *adj[prev].entry(to).or_default() += weight;
+                    *adj[to].entry(prev).or_default() += weight;
+                }
+            }
+            let merged = std::mem::take(&mut members[last]);
+            members[prev].extend(merged);
+            active.retain(|&idx| idx != last);
+        }
+
+        best.map(|(weight, mut side)| {
+            side.sort_unstable();
+            (weight, side)
+        })
+    }
+
+    /// Largest set of pairwise connected nodes, sorted
+    pub fn max_clique(&self) -> Vec<usize> {
+        let neighbors = (0..self.len())
+            .map(|idx| self.neighbors(idx).filter(|&to| to != idx).collect::<HashSet<_>>())
+            .collect::<Vec<_>>();
+        let mut best = Vec::new();
+        bron_kerbosch(&neighbors, &mut Vec::new(), (0..self.len()).collect(), HashSet::new(), &mut best);
+        best.sort_unstable();
+        best
+    }
+
+    /// Tarjan's strongly connected components, each sorted,
+    /// listed in reverse topological order of the condensed graph
+    #[cfg(test)]
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        const UNVISITED: usize = usize::MAX;
+        let n = self.len();
+        let mut index = vec![UNVISITED; n];
+        let mut low = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next = 0;
+
+        for root in 0..n {
+            if index[root] != UNVISITED {
+                continue;
+            }
+            // explicit call stack of nodes and their next edge to follow
+            let mut calls = vec![(root, 0)];
+            index[root] = next;
+            low[root] = next;
+            next += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((idx, edge)) = calls.last_mut() {
+                let idx = *idx;
+                if let Some(&(to, _)) = self.adj[idx].get(*edge) {
+                    *edge += 1;
+                    if index[to] == UNVISITED {
+                        index[to] = next;
+                        low[to] = next;
+                        next += 1;
+                        stack.push(to);
+                        on_stack[to] = true;
+                        calls.push((to, 0));
+                    } else if on_stack[to] {
+                        low[idx] = low[idx].min(index[to]);
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if let Some(&(parent, _)) = calls.last() {
+                    low[parent] = low[parent].min(low[idx]);
+                }
+                if low[idx] == index[idx] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == idx {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+
+    /// Kahn's topological order of a directed graph, None if it has a cycle
+    pub fn topological_sort(&self) -> Option<Vec<usize>> {
+        assert!(self.directed, "topological sort needs a directed graph");
+        let mut in_degree = vec![0; self.len()];
+        for to in (0..self.len()).flat_map(|idx| self.neighbors(idx)) {
+            in_degree[to] += 1;
+        }
+
+        let mut ready = (0..self.len()).filter(|&idx| in_degree[idx] == 0).collect::<VecDeque<_>>();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(idx) = ready.pop_front() {
+            order.push(idx);
+            for to in self.neighbors(idx) {
+                in_degree[to] -= 1;
+                if in_degree[to] == 0 {
+                    ready.push_back(to);
+                }
+            }
+        }
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// Number of distinct paths from `start` to `end`,
+    /// panics if the nodes reachable from `start` form a cycle
+    pub fn count_paths(&self, start: usize, end: usize) -> u64 {
+        let mut counts = vec![None; self.len()];
+        let mut on_path = vec![false; self.len()];
+        on_path[start] = true;
+        // explicit call stack of nodes and their next edge to follow,
+        // a node is counted after all its successors
+        let mut calls = vec![(start, 0)];
+
+        while let Some((idx, edge)) = calls.last_mut() {
+            let idx = *idx;
+            // paths stop at `end`
+            if let Some(&(to, _)) = self.adj[idx].get(*edge).filter(|_| idx != end) {
+                *edge += 1;
+                if counts[to].is_none() {
+                    assert!(!on_path[to], "cycle reachable from start");
+                    on_path[to] = true;
+                    calls.push((to, 0));
+                }
+                continue;
+            }
+
+            calls.pop();
+            on_path[idx] = false;
+            let count = if idx == end { 1 } else { self.neighbors(idx).map(|to| counts[to].unwrap()).sum() };
+            counts[idx] = Some(count);
+        }
+
+        counts[start].unwrap()
+    }
+}
+
+/// Bron–Kerbosch with pivoting, only tracks the largest clique
+fn bron_kerbosch(
+    neighbors: &[HashSet<usize>],
+    clique: &mut Vec<usize>,
+    mut candidates: HashSet<usize>,
+    mut excluded: HashSet<usize>,
+    best: &mut Vec<usize>,
+) {
+    if candidates.is_empty() {
+        if excluded.is_empty() && clique.len() > best.len() {
+            *best = clique.clone();
+        }
+        return;
+    }
+    if clique.len() + candidates.len() <= best.len() {
+        // can't beat the best clique anymore
+        return;
+    }
+
+    // neighbours of the pivot are covered by the branches of other nodes
+    let pivot = *candidates
+        .iter()
+        .chain(&excluded)
+        .max_by_key(|&&idx| neighbors[idx].intersection(&candidates).count())
+        .unwrap();
+    let branches = candidates.difference(&neighbors[pivot]).copied().collect::<Vec<_>>();
+    for idx in branches {
+        clique.push(idx);
+        bron_kerbosch(
+            neighbors,
+            clique,
+            candidates.intersection(&neighbors[idx]).copied().collect(),
+            excluded.intersection(&neighbors[idx]).copied().collect(),
+            best,
+        );
+        clique.pop();
+        candidates.remove(&idx);
+        excluded.insert(idx);
+    }
+}
+
+#[test]
+pub fn test() {
+    // two triangles joined by a single bridge
+    let mut graph = Graph::undirected();
+    for (a, b) in [("a", "b"), ("b", "c"), ("c", "a"), ("c", "d"), ("d", "e"), ("e", "f"), ("f", "d")] {
+        graph.edge(a, b);
+    }
+    assert_eq!(graph.len(), 6);
+    assert_eq!(graph.index("d"), Some(3));
+    assert_eq!(graph.label(4), "e");
+    assert!(graph.has_edge(3, 2) && !graph.has_edge(0, 4));
+    let (weight, side) = graph.min_cut().unwrap();
+    assert_eq!(weight, 1);
+    assert!(side == vec![0, 1, 2] || side == vec![3, 4, 5]);
+    assert_eq!(graph.max_clique().len(), 3);
+    assert_eq!(graph.strongly_connected_components().len(), 1);
+
+    // heavier edges move the cut
+    let mut graph = Graph::undirected();
+    graph.weighted_edge(1, 2, 5);
+    graph.weighted_edge(2, 3, 1);
+    graph.weighted_edge(3, 4, 5);
+    graph.weighted_edge(4, 1, 2);
+    let (weight, side) = graph.min_cut().unwrap();
+    assert_eq!(weight, 3);
+    assert!(side == vec![0, 1] || side == vec![2, 3]);
+    // disconnected nodes are cut for free
+    graph.node(5);
+    assert_eq!(graph.min_cut().unwrap().0, 0);
+    assert_eq!(Graph::<u8>::undirected().min_cut(), None);
+
+    // cliques of 4 and 5 with extra noise
+    let mut graph = Graph::undirected();
+    let clique = [10, 11, 12, 13, 14];
+    for (i, &a) in clique.iter().enumerate() {
+        for &b in &clique[i + 1..] {
+            graph.edge(a, b);
+        }
+    }
+    for (a, b) in [(1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4), (4, 10), (2, 12), (14, 20)] {
+        graph.edge(a, b);
+    }
+    let best = graph.max_clique().into_iter().map(|idx| graph.label(idx)).collect::<Vec<_>>();
+    assert_eq!(best, clique);
+
+    // directed: cycle a-b-c feeding into d-e
+    let mut graph = Graph::directed();
+    for (a, b) in [("a", "b"), ("b", "c"), ("c", "a"), ("c", "d"), ("d", "e"), ("e", "d"), ("f", "e")] {
+        graph.edge(a, b);
+    }
+    let sccs = graph.strongly_connected_components();
+    assert_eq!(sccs, vec![vec![3, 4], vec![0, 1, 2], vec![5]]);
+    assert_eq!(graph.topological_sort(), None);
+    // paths end at `end`, only cycles before it are a problem
+    assert_eq!(graph.count_paths(graph.index("f").unwrap(), graph.index("e").unwrap()), 1);
+    let cycle = std::panic::catch_unwind(|| graph.count_paths(0, graph.index("e").unwrap()));
+    assert!(cycle.is_err());
+
+    // DAG: diamond lattice has binomial path counts
+    let mut graph = Graph::directed();
+    for x in 0..10u32 {
+        for y in 0..10u32 {
+            if x < 9 {
+                graph.edge((x, y), (x + 1, y));
+            }
+            if y < 9 {
+                graph.edge((x, y), (x, y + 1));
+            }
+        }
+    }
+    let (start, end) = (graph.index((0, 0)).unwrap(), graph.index((9, 9)).unwrap());
+    assert_eq!(graph.count_paths(start, end), 48620);
+    assert_eq!(graph.count_paths(end, start), 0);
+    assert_eq!(graph.count_paths(start, start), 1);
+    let order = graph.topological_sort().unwrap();
+    let mut position = vec![0; graph.len()];
+    for (i, &idx) in order.iter().enumerate() {
+        position[idx] = i;
+    }
+    assert!((0..graph.len()).all(|idx| graph.neighbors(idx).all(|to| position[idx] < position[to])));
+    assert_eq!(graph.strongly_connected_components().len(), 100);
+}
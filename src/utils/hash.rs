@@ -0,0 +1,113 @@
+//! Fast non-cryptographic hashing for hot lookup tables
+//!
+//! Uses the multiply-rotate scheme of rustc's `FxHasher`. It gives no
+//! protection against crafted collisions, which puzzle input can't exploit.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{BuildHasherDefault, Hasher},
+};
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+/// Hashes machine words with one rotate, xor and multiply each
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FastHasher {
+    hash: u64,
+}
+
+impl FastHasher {
+    #[inline]
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FastHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.add(i as u64);
+        self.add((i >> 64) as u64);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+pub type FastBuildHasher = BuildHasherDefault<FastHasher>;
+/// `HashMap` with [`FastHasher`], created with `default()` or `with_capacity_and_hasher()`
+pub type FastHashMap<K, V> = HashMap<K, V, FastBuildHasher>;
+/// `HashSet` with [`FastHasher`], created with `default()` or `with_capacity_and_hasher()`
+pub type FastHashSet<T> = HashSet<T, FastBuildHasher>;
+
+#[test]
+pub fn test() {
+    use std::hash::{BuildHasher, Hash};
+
+    let hash = |value: &dyn Fn(&mut FastHasher)| {
+        let mut hasher = FastHasher::default();
+        value(&mut hasher);
+        hasher.finish()
+    };
+    // deterministic, order and length sensitive
+    assert_eq!(hash(&|h| (1, 2).hash(h)), hash(&|h| (1, 2).hash(h)));
+    assert_ne!(hash(&|h| (1, 2).hash(h)), hash(&|h| (2, 1).hash(h)));
+    assert_ne!(hash(&|h| "ab".hash(h)), hash(&|h| "abc".hash(h)));
+    assert_ne!(hash(&|h| "abcdefgh1".hash(h)), hash(&|h| "abcdefgh2".hash(h)));
+    assert_eq!(FastBuildHasher::default().hash_one(42u32), FastBuildHasher::default().hash_one(42u32));
+
+    // small grid coordinates don't collide
+    let hashes = (0..100i16)
+        .flat_map(|x| (0..100i16).map(move |y| (x, y)))
+        .map(|p| FastBuildHasher::default().hash_one(p))
+        .collect::<FastHashSet<_>>();
+    assert_eq!(hashes.len(), 10000);
+
+    let mut map = FastHashMap::default();
+    for i in 0..1000u32 {
+        *map.entry(i % 7).or_insert(0) += i;
+    }
+    assert_eq!(map.len(), 7);
+    assert_eq!(map[&0], (0..1000).step_by(7).sum());
+}
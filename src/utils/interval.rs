@@ -0,0 +1,296 @@
+//! Intervals of integers, normalised sets of them and piecewise mappings
+//!
+//! Intervals are half-open `start..end`, puzzle ranges with inclusive ends are
+//! converted with [`Interval::inclusive`].
+
+use std::{
+    fmt,
+    ops::{Add, Sub},
+};
+
+/// Integer type usable as interval bound
+pub trait Integer: Copy + Ord + Default + Add<Output = Self> + Sub<Output = Self> + From<u8> {}
+
+impl<T> Integer for T where T: Copy + Ord + Default + Add<Output = T> + Sub<Output = T> + From<u8> {}
+
+/// Half-open interval `start..end`, empty if `end <= start`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Integer> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    /// Interval from `first` to `last`, both included
+    pub fn inclusive(first: T, last: T) -> Self {
+        Self::new(first, last + T::from(1))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    /// number of contained values
+    pub fn len(&self) -> T {
+        if self.is_empty() { T::default() } else { self.end - self.start }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// Parts below and from `value` on
+    pub fn split_at(&self, value: T) -> (Self, Self) {
+        let value = value.clamp(self.start, self.end.max(self.start));
+        (Self::new(self.start, value), Self::new(value, self.end))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// Set of values as sorted, disjoint and non-adjacent intervals
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { intervals: Vec::new() }
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// number of contained values
+    pub fn len(&self) -> T {
+        self.intervals.iter().fold(T::default(), |sum, interval| sum + interval.len())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.intervals.partition_point(|interval| interval.end <= value);
+        self.intervals.get(i).is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        // intervals overlapping or touching the new one are merged into it
+        let first = self.intervals.partition_point(|other| other.end < interval.start);
+        let last = self.intervals.partition_point(|other| other.start <= interval.end);
+        let merged = self.intervals[first..last].iter().fold(interval, |merged, other| {
+            Interval::new(merged.start.min(other.start), merged.end.max(other.end))
+        });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    #[cfg(test)]
+    pub fn union(&self, other: &Self) -> Self {
+        self.intervals.iter().chain(&other.intervals).copied().collect()
+    }
+
+    #[cfg(test)]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            let common = a.intersection(b);
+            if !common.is_empty() {
+                intervals.push(common);
+            }
+            if a.end < b.end { i += 1 } else { j += 1 }
+        }
+        Self { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let mut j = 0;
+        for &interval in &self.intervals {
+            let mut rest = interval;
+            // skip intervals of other ending before
+            while other.intervals.get(j).is_some_and(|b| b.end <= rest.start) {
+                j += 1;
+            }
+            let mut k = j;
+            while let Some(b) = other.intervals.get(k).filter(|b| b.start < rest.end) {
+                let (below, _) = rest.split_at(b.start);
+                if !below.is_empty() {
+                    intervals.push(below);
+                }
+                rest = rest.split_at(b.end).1;
+                k += 1;
+            }
+            if !rest.is_empty() {
+                intervals.push(rest);
+            }
+        }
+        Self { intervals }
+    }
+}
+
+impl<T: Integer> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut intervals = iter.into_iter().filter(|interval| !interval.is_empty()).collect::<Vec<_>>();
+        intervals.sort_unstable_by_key(|interval| interval.start);
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+        Self { intervals: merged }
+    }
+}
+
+/// Piecewise translation of source intervals to destination intervals, values
+/// outside all sources map to themselves
+#[derive(Clone, Debug, Default)]
+pub struct IntervalMap<T> {
+    /// non-overlapping sources sorted by start, with destination start
+    pieces: Vec<(Interval<T>, T)>,
+}
+
+impl<T: Integer> IntervalMap<T> {
+    pub fn new() -> Self {
+        Self { pieces: Vec::new() }
+    }
+
+    /// number of mapped intervals
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.pieces.len()
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.pieces.is_empty()
+    }
+
+    /// Maps `source` to the interval of the same length starting at `destination`
+    pub fn insert(&mut self, source: Interval<T>, destination: T) {
+        let i = self.pieces.partition_point(|(other, _)| other.start < source.start);
+        let overlaps = |j: usize| self.pieces.get(j).is_some_and(|(other, _)| !other.intersection(&source).is_empty());
+        assert!(!overlaps(i) && (i == 0 || !overlaps(i - 1)), "overlapping sources");
+        self.pieces.insert(i, (source, destination));
+    }
+
+    pub fn map(&self, value: T) -> T {
+        let i = self.pieces.partition_point(|(source, _)| source.end <= value);
+        match self.pieces.get(i) {
+            Some(&(source, destination)) if source.contains(value) => destination + (value - source.start),
+            _ => value,
+        }
+    }
+
+    /// Image of all values of the interval
+    pub fn map_interval(&self, interval: Interval<T>) -> IntervalSet<T> {
+        let mut image = Vec::new();
+        let mut rest = interval;
+        for &(source, destination) in &self.pieces {
+            if rest.is_empty() {
+                break;
+            }
+            // unmapped values before the source
+            let (before, after) = rest.split_at(source.start);
+            image.push(before);
+            let (inside, after) = after.split_at(source.end);
+            if !inside.is_empty() {
+                let start = destination + (inside.start - source.start);
+                image.push(Interval::new(start, start + inside.len()));
+            }
+            rest = after;
+        }
+        image.push(rest);
+        image.into_iter().collect()
+    }
+
+    /// Image of all values of the set
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.intervals().iter().flat_map(|&interval| self.map_interval(interval).intervals).collect()
+    }
+}
+
+#[test]
+pub fn test() {
+    let set = |intervals: &[(i32, i32)]| intervals.iter().map(|&(a, b)| Interval::new(a, b)).collect::<IntervalSet<_>>();
+
+    let interval = Interval::inclusive(3, 5);
+    assert_eq!(interval, Interval::new(3, 6));
+    assert_eq!(interval.len(), 3);
+    assert!(interval.contains(5) && !interval.contains(6));
+    assert_eq!(Interval::new(5, 3).len(), 0);
+    assert!(interval.intersection(&Interval::new(6, 9)).is_empty());
+    assert_eq!(interval.split_at(4), (Interval::new(3, 4), Interval::new(4, 6)));
+    assert_eq!(interval.split_at(9), (Interval::new(3, 6), Interval::new(6, 6)));
+    assert_eq!(interval.to_string(), "3..6");
+
+    // overlapping and adjacent intervals are merged, empty ones dropped
+    let a = set(&[(10, 15), (3, 6), (16, 21), (12, 19), (4, 4), (21, 22)]);
+    assert_eq!(a.intervals(), &[Interval::new(3, 6), Interval::new(10, 22)]);
+    assert_eq!(a.len(), 15);
+    assert!(a.contains(3) && a.contains(21) && !a.contains(6) && !a.contains(9) && !a.contains(22));
+
+    let mut b = set(&[(0, 2), (5, 11), (14, 16), (20, 30)]);
+    assert_eq!(a.union(&b), set(&[(0, 2), (3, 30)]));
+    assert_eq!(a.intersection(&b), set(&[(5, 6), (10, 11), (14, 16), (20, 22)]));
+    assert_eq!(a.difference(&b), set(&[(3, 5), (11, 14), (16, 20)]));
+    assert_eq!(b.difference(&a), set(&[(0, 2), (6, 10), (22, 30)]));
+    assert!(a.difference(&a).is_empty());
+    b.insert(Interval::new(2, 14));
+    assert_eq!(b, set(&[(0, 16), (20, 30)]));
+    b.insert(Interval::new(17, 18));
+    assert_eq!(b, set(&[(0, 16), (17, 18), (20, 30)]));
+
+    // random sets agree with sets of values
+    let mut rng = crate::utils::Rng::new(44);
+    let mut random_set = || {
+        let intervals = (0..rng.next_u64() % 6).map(|_| {
+            let start = (rng.next_u64() % 40) as i32;
+            Interval::new(start, start + (rng.next_u64() % 8) as i32)
+        });
+        intervals.collect::<IntervalSet<_>>()
+    };
+    for _ in 0..100 {
+        let (a, b) = (random_set(), random_set());
+        for (result, operation) in [
+            (a.union(&b), (|x, y| x || y) as fn(bool, bool) -> bool),
+            (a.intersection(&b), |x, y| x && y),
+            (a.difference(&b), |x, y| x && !y),
+        ] {
+            for value in -1..50 {
+                assert_eq!(result.contains(value), operation(a.contains(value), b.contains(value)));
+            }
+            assert_eq!(result.len(), (-1..50).filter(|&value| result.contains(value)).count() as i32);
+        }
+    }
+
+    let mut map = IntervalMap::new();
+    assert!(map.is_empty());
+    map.insert(Interval::new(98, 100), 50);
+    map.insert(Interval::new(50, 98), 52);
+    assert_eq!([0, 49, 50, 79, 97, 98, 99, 100].map(|value| map.map(value)), [0, 49, 52, 81, 99, 50, 51, 100]);
+    assert_eq!(map.map_interval(Interval::new(79, 93)), set(&[(81, 95)]));
+    assert_eq!(map.map_interval(Interval::new(40, 99)), set(&[(40, 50), (50, 51), (52, 100)]));
+    assert_eq!(map.map_set(&set(&[(10, 20), (97, 105)])), set(&[(10, 20), (50, 52), (99, 105)]));
+}
@@ -0,0 +1,576 @@
+//! Exact linear algebra over integers and rationals
+//!
+//! Matrices are row vectors of `i128`, reduced without fractions, so results
+//! stay exact for large puzzle coordinates. Overflows are reported as errors
+//! by the matrix functions and panic in `Rational` arithmetic instead of
+//! wrapping around.
+
+use std::{
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use super::gcd;
+
+/// Exact fraction in lowest terms with positive denominator
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rational {
+    pub num: i128,
+    pub den: i128,
+}
+
+const OVERFLOW: &str = "overflow in exact arithmetic";
+
+/// `i128` result of a checked operation, panics on overflow
+fn checked(value: Option<i128>) -> i128 {
+    value.expect(OVERFLOW)
+}
+
+impl Rational {
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "zero denominator");
+        let divisor = gcd(num, den).abs() * den.signum();
+        Self {
+            num: num / divisor,
+            den: den / divisor,
+        }
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(num: i128) -> Self {
+        Self { num, den: 1 }
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let num = checked(
+            checked(self.num.checked_mul(other.den))
+                .checked_add(checked(other.num.checked_mul(self.den))),
+        );
+        Self::new(num, checked(self.den.checked_mul(other.den)))
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            num: checked(self.num.checked_neg()),
+            den: self.den,
+        }
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            checked(self.num.checked_mul(other.num)),
+            checked(self.den.checked_mul(other.den)),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        Self::new(
+            checked(self.num.checked_mul(other.den)),
+            checked(self.den.checked_mul(other.num)),
+        )
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.den {
+            1 => write!(f, "{}", self.num),
+            den => write!(f, "{}/{den}", self.num),
+        }
+    }
+}
+
+/// Brings the first `columns` columns of the matrix into reduced row echelon form
+/// using fraction-free Gauss-Jordan elimination
+///
+/// Pivots are positive but not normalized to 1, each row is divided by the gcd
+/// of its entries instead. Returns the pivot column of each leading row, the
+/// remaining rows are zero within `columns`.
+/// https://en.wikipedia.org/wiki/Row_echelon_form#Reduced_row_echelon_form
+pub fn row_reduce(matrix: &mut [Vec<i128>], columns: usize) -> Result<Vec<usize>, &'static str> {
+    let mut pivots = Vec::new();
+    for col in 0..columns {
+        let row = pivots.len();
+        let Some(pivot_row) = (row..matrix.len()).find(|&i| matrix[i][col] != 0) else {
+            continue;
+        };
+        matrix.swap(row, pivot_row);
+        if matrix[row][col] < 0 {
+            matrix[row].iter_mut().for_each(|a| *a = -*a);
+        }
+        normalize(&mut matrix[row]);
+        let pivot_row = matrix[row].clone();
+        for (i, other) in matrix.iter_mut().enumerate() {
+            if i != row && other[col] != 0 {
+                let (pivot, factor) = (pivot_row[col], other[col]);
+                for (a, &b) in other.iter_mut().zip(&pivot_row) {
+                    *a = a
+                        .checked_mul(pivot)
+                        .zip(factor.checked_mul(b))
+                        .and_then(|(a, b)| a.checked_sub(b))
+                        .ok_or(OVERFLOW)?;
+                }
+                normalize(other);
+            }
+        }
+        pivots.push(col);
+    }
+    Ok(pivots)
+}
+
+/// Divides the row by the greatest common divisor of its entries
+fn normalize(row: &mut [i128]) {
+    let divisor = row.iter().fold(0, |d, &a| gcd(d, a.abs()));
+    if divisor > 1 {
+        row.iter_mut().for_each(|a| *a /= divisor);
+    }
+}
+
+/// Number of linearly independent rows
+pub fn rank(matrix: &[Vec<i128>]) -> Result<usize, &'static str> {
+    let columns = matrix.first().map_or(0, Vec::len);
+    Ok(row_reduce(&mut matrix.to_vec(), columns)?.len())
+}
+
+/// Determinant of a square matrix, using the Bareiss algorithm
+#[cfg(test)]
+pub fn determinant(matrix: &[Vec<i128>]) -> Result<i128, &'static str> {
+    let n = matrix.len();
+    if matrix.iter().any(|row| row.len() != n) {
+        return Err("matrix is not square");
+    }
+    let mut m = matrix.to_vec();
+    let mut sign = 1;
+    let mut previous = 1;
+    for k in 0..n {
+        let Some(pivot_row) = (k..n).find(|&i| m[i][k] != 0) else {
+            return Ok(0);
+        };
+        if pivot_row != k {
+            m.swap(k, pivot_row);
+            sign = -sign;
+        }
+        for i in k + 1..n {
+            for j in k + 1..n {
+                // exact division, the result is a minor of the matrix
+                m[i][j] = m[i][j]
+                    .checked_mul(m[k][k])
+                    .zip(m[i][k].checked_mul(m[k][j]))
+                    .and_then(|(a, b)| a.checked_sub(b))
+                    .ok_or(OVERFLOW)?
+                    / previous;
+            }
+        }
+        previous = m[k][k];
+    }
+    Ok(if n == 0 { 1 } else { sign * m[n - 1][n - 1] })
+}
+
+/// Basis of the solutions of `matrix * x = 0`
+#[cfg(test)]
+pub fn null_space(matrix: &[Vec<i128>]) -> Result<Vec<Vec<Rational>>, &'static str> {
+    let columns = matrix.first().map_or(0, Vec::len);
+    let mut rows = matrix.to_vec();
+    let pivots = row_reduce(&mut rows, columns)?;
+    Ok((0..columns)
+        .filter(|col| !pivots.contains(col))
+        .map(|free| {
+            let mut vector = vec![Rational::from(0); columns];
+            vector[free] = Rational::from(1);
+            for (row, &col) in rows.iter().zip(&pivots) {
+                vector[col] = Rational::new(-row[free], row[col]);
+            }
+            vector
+        })
+        .collect())
+}
+
+/// Unique solution of a linear system given as augmented matrix
+///
+/// Rows are equations, with coefficients followed by the right-hand side.
+/// There may be more equations than variables, as long as they are consistent.
+pub fn solve(matrix: &[Vec<i128>]) -> Result<Vec<Rational>, &'static str> {
+    let num_vars = variable_count(matrix)?;
+    let mut rows = matrix.to_vec();
+    let pivots = row_reduce(&mut rows, num_vars)?;
+    if rows[pivots.len()..].iter().any(|row| row[num_vars] != 0) {
+        return Err("no solution");
+    }
+    if pivots.len() < num_vars {
+        return Err("infinitely many solutions");
+    }
+    Ok(rows
+        .iter()
+        .zip(&pivots)
+        .map(|(row, &col)| Rational::new(row[num_vars], row[col]))
+        .collect())
+}
+
+/// Number of variables of an augmented matrix
+fn variable_count(matrix: &[Vec<i128>]) -> Result<usize, &'static str> {
+    match matrix.first().map(Vec::len) {
+        Some(len) if len > 0 && matrix.iter().all(|row| row.len() == len) => Ok(len - 1),
+        Some(_) => Err("invalid augmented matrix"),
+        None => Err("no equations"),
+    }
+}
+
+/// Solves a system of linear equations for non-negative integer solutions, minimizing the sum of variables.
+///
+/// Rows of `matrix` are equations, with coefficients followed by the right-hand side.
+/// Uses branch-and-bound over the free variables of the reduced system. Their ranges
+/// are derived from the equations where possible. Otherwise they are limited by the
+/// objective, as no variable exceeds the sum of all: the search is repeated with a
+/// doubling limit on the sum, up to the size a smallest solution is known to have.
+/// Gives up with an error after a fixed number of branches.
+pub fn solve_min_integer_sum(matrix: &[Vec<i128>]) -> Result<u64, &'static str> {
+    min_integer_sum(matrix, MAX_SEARCH_STEPS)
+}
+
+/// Branches of the integer search before giving up, far more than bounded puzzle
+/// systems need, but reached in time when unbounded variables make the search
+/// run through huge ranges
+const MAX_SEARCH_STEPS: u64 = 1 << 28;
+
+fn min_integer_sum(matrix: &[Vec<i128>], max_steps: u64) -> Result<u64, &'static str> {
+    let num_vars = variable_count(matrix)?;
+    let mut rows = matrix.to_vec();
+    let pivots = row_reduce(&mut rows, num_vars)?;
+    if rows[pivots.len()..].iter().any(|row| row[num_vars] != 0) {
+        return Err("no solution");
+    }
+    rows.truncate(pivots.len());
+
+    let free_vars = (0..num_vars)
+        .filter(|col| !pivots.contains(col))
+        .collect::<Vec<_>>();
+    let row_bounds = free_vars
+        .iter()
+        .map(|&col| upper_bound(matrix, &rows, col))
+        .collect::<Vec<_>>();
+
+    // objective scaled by the common denominator of the pivots, as a function of the free variables:
+    // sum of variables = (constant + sum(weight * free variable)) / denominator
+    let pivot_values = pivots
+        .iter()
+        .zip(&rows)
+        .map(|(&col, row)| row[col])
+        .collect::<Vec<_>>();
+    let denominator = pivot_values
+        .iter()
+        .try_fold(1, |d, &p| mul(d / gcd(d, p), p))?;
+    let scaled_sum = |col: usize| {
+        rows.iter()
+            .zip(&pivot_values)
+            .try_fold(0, |sum, (row, &p)| add(sum, mul(denominator / p, row[col])?))
+    };
+    let constant = scaled_sum(num_vars)?;
+    let weights = free_vars
+        .iter()
+        .map(|&col| sub(denominator, scaled_sum(col)?))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let remaining = rows.iter().map(|row| row[num_vars]).collect::<Vec<_>>();
+    let max_limit = solution_size_bound(matrix);
+    let mut limit = remaining.iter().map(|r| r.saturating_abs()).max().unwrap_or(0).max(1);
+    let mut steps_left = max_steps;
+    loop {
+        // without a row bound, a variable can't exceed the limit on the sum
+        let all_bounded = row_bounds.iter().all(Option::is_some);
+        let bounds = row_bounds
+            .iter()
+            .map(|bound| bound.unwrap_or(limit))
+            .collect::<Vec<_>>();
+        let mut search = IntegerSearch {
+            rows: &rows,
+            pivot_values: &pivot_values,
+            free_vars: &free_vars,
+            bounds: &bounds,
+            weights: &weights,
+            denominator,
+            values: Vec::with_capacity(free_vars.len()),
+            best: (!all_bounded).then(|| limit.saturating_add(1)),
+            steps_left,
+        };
+        search.branch(&remaining, constant)?;
+        steps_left = search.steps_left;
+        match search.best {
+            Some(best) if all_bounded || best <= limit => return Ok(best as u64),
+            _ if all_bounded || limit >= max_limit => {
+                return Err("no non-negative integer solution");
+            }
+            _ => limit = limit.saturating_mul(2).min(max_limit),
+        }
+    }
+}
+
+/// Sum, difference and product reporting overflow as error
+fn add(a: i128, b: i128) -> Result<i128, &'static str> {
+    a.checked_add(b).ok_or(OVERFLOW)
+}
+
+fn sub(a: i128, b: i128) -> Result<i128, &'static str> {
+    a.checked_sub(b).ok_or(OVERFLOW)
+}
+
+fn mul(a: i128, b: i128) -> Result<i128, &'static str> {
+    a.checked_mul(b).ok_or(OVERFLOW)
+}
+
+/// Largest value of a non-negative variable, from an equation whose other terms
+/// are non-negative, in the original or reduced system
+fn upper_bound(matrix: &[Vec<i128>], rows: &[Vec<i128>], col: usize) -> Option<i128> {
+    matrix
+        .iter()
+        .chain(rows)
+        .filter(|row| row[col] > 0 && row[..row.len() - 1].iter().all(|&a| a >= 0))
+        .map(|row| row[row.len() - 1].max(-1).div_euclid(row[col]))
+        .min()
+}
+
+/// Sum of variables that some optimal non-negative integer solution stays below, if
+/// there is any: `n * (m * a)^(2m + 1)` for `m` equations in `n` variables with
+/// coefficients of absolute value up to `a` (Papadimitriou, 1981)
+fn solution_size_bound(matrix: &[Vec<i128>]) -> i128 {
+    let (m, n) = (matrix.len() as i128, matrix[0].len() as i128 - 1);
+    let a = matrix
+        .iter()
+        .flatten()
+        .map(|a| a.abs())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let base = m.saturating_mul(a);
+    (0..2 * m + 1).fold(n, |bound, _| bound.saturating_mul(base))
+}
+
+/// Branch-and-bound over the free variables of a reduced system
+struct IntegerSearch<'a> {
+    rows: &'a [Vec<i128>],
+    pivot_values: &'a [i128],
+    free_vars: &'a [usize],
+    bounds: &'a [i128],
+    weights: &'a [i128],
+    denominator: i128,
+    values: Vec<i128>,
+    /// smallest sum of variables found so far, or the exclusive limit on it
+    best: Option<i128>,
+    steps_left: u64,
+}
+
+impl IntegerSearch<'_> {
+    /// `remaining` are the right-hand sides after subtracting the assigned free variables,
+    /// `objective` the scaled sum of variables for those
+    fn branch(&mut self, remaining: &[i128], objective: i128) -> Result<(), &'static str> {
+        self.steps_left = self.steps_left.checked_sub(1).ok_or("search limit exceeded")?;
+        let k = self.values.len();
+        // lowest objective and highest right-hand sides reachable with the unassigned variables
+        let lowest = add(objective, self.lowest_change(k)?)?;
+        if self.exceeds_best(lowest) {
+            return Ok(());
+        }
+        for (i, row) in self.rows.iter().enumerate() {
+            let highest = (k..self.free_vars.len()).try_fold(remaining[i], |highest, f| {
+                sub(highest, mul(row[self.free_vars[f]], self.bounds[f])?.min(0))
+            })?;
+            if highest < 0 {
+                return Ok(());
+            }
+        }
+
+        if k == self.free_vars.len() {
+            // pivot variables are determined
+            if remaining
+                .iter()
+                .zip(self.pivot_values)
+                .all(|(&r, &p)| r % p == 0)
+            {
+                let sum = objective / self.denominator;
+                self.best = Some(self.best.map_or(sum, |best| best.min(sum)));
+            }
+            return Ok(());
+        }
+
+        let col = self.free_vars[k];
+        let mut next = remaining.to_vec();
+        let rest = self.lowest_change(k + 1)?;
+        // values lowering the objective first, so the remaining ones can be skipped
+        // once the lowest reachable objective is not better than the best
+        let values = 0..=self.bounds[k];
+        let values: Box<dyn Iterator<Item = i128>> = if self.weights[k] < 0 {
+            Box::new(values.rev())
+        } else {
+            Box::new(values)
+        };
+        for value in values {
+            let objective = add(objective, mul(self.weights[k], value)?)?;
+            if self.exceeds_best(add(objective, rest)?) {
+                break;
+            }
+            for (i, row) in self.rows.iter().enumerate() {
+                next[i] = sub(remaining[i], mul(row[col], value)?)?;
+            }
+            self.values.push(value);
+            self.branch(&next, objective)?;
+            self.values.pop();
+        }
+        Ok(())
+    }
+
+    /// Lowest change of the scaled objective by the free variables from index `k` on
+    fn lowest_change(&self, k: usize) -> Result<i128, &'static str> {
+        (k..self.free_vars.len()).try_fold(0, |sum, f| {
+            add(sum, mul(self.weights[f], self.bounds[f])?.min(0))
+        })
+    }
+
+    /// Whether a scaled objective can't improve on the best sum
+    fn exceeds_best(&self, objective: i128) -> bool {
+        self.best
+            .and_then(|best| best.checked_mul(self.denominator))
+            .is_some_and(|best| objective >= best)
+    }
+}
+
+#[test]
+pub fn test() {
+    let matrix = |rows: &[&[i128]]| rows.iter().map(|row| row.to_vec()).collect::<Vec<_>>();
+
+    assert_eq!(Rational::new(6, -4), Rational::new(-3, 2));
+    assert_eq!(
+        (Rational::new(1, 2) - Rational::new(1, 3)).to_string(),
+        "1/6"
+    );
+
+    let m = matrix(&[&[2, 0, 1], &[1, 3, 2], &[1, 1, 2]]);
+    assert_eq!(determinant(&m), Ok(6));
+    assert_eq!(rank(&m), Ok(3));
+    assert_eq!(determinant(&matrix(&[&[0, 1], &[1, 0]])), Ok(-1));
+    assert_eq!(determinant(&matrix(&[&[1, 2], &[2, 4]])), Ok(0));
+    assert_eq!(
+        determinant(&matrix(&[&[1, 2, 3], &[4, 5, 6], &[7, 8, 10]])),
+        Ok(-3)
+    );
+    assert_eq!(
+        determinant(&matrix(&[&[1, 2]])),
+        Err("matrix is not square")
+    );
+
+    let m = matrix(&[&[1, 2, 3], &[2, 4, 6], &[1, 0, 1]]);
+    assert_eq!(rank(&m), Ok(2));
+    assert_eq!(
+        null_space(&m),
+        Ok(vec![vec![
+            Rational::from(-1),
+            Rational::from(-1),
+            Rational::from(1)
+        ]])
+    );
+
+    // 2x + y = 5, x - y = 1/2 with a redundant equation
+    let solution = solve(&matrix(&[&[2, 1, 5], &[2, -2, 1], &[4, -4, 2]]));
+    assert_eq!(
+        solution,
+        Ok(vec![Rational::new(11, 6), Rational::new(4, 3)])
+    );
+    assert_eq!(
+        solve(&matrix(&[&[1, 1, 1], &[1, 1, 2]])),
+        Err("no solution")
+    );
+    assert_eq!(
+        solve(&matrix(&[&[1, 1, 1]])),
+        Err("infinitely many solutions")
+    );
+    // coordinates beyond the exact range of f64
+    let big = 123_456_789_012_345_678;
+    let solution = solve(&matrix(&[&[3, 1, 3 * big + 7], &[1, -1, big - 7]]));
+    assert_eq!(solution, Ok(vec![Rational::from(big), Rational::from(7)]));
+    // elimination exceeding i128
+    let huge = i128::MAX / 2 - 1;
+    assert_eq!(
+        solve(&matrix(&[&[huge, 3, 1], &[3, huge, 1]])),
+        Err("overflow in exact arithmetic")
+    );
+
+    // x0 + x1 = 3, x1 + x2 = 5
+    assert_eq!(
+        solve_min_integer_sum(&matrix(&[&[1, 1, 0, 3], &[0, 1, 1, 5]])),
+        Ok(5)
+    );
+    // optimum beyond small free variable ranges
+    assert_eq!(solve_min_integer_sum(&matrix(&[&[1, 2, 3000]])), Ok(1500));
+    assert_eq!(
+        solve_min_integer_sum(&matrix(&[&[3, 2, 1, 12345]])),
+        Ok(4115)
+    );
+    // negative coefficients
+    assert_eq!(
+        solve_min_integer_sum(&matrix(&[&[1, -1, -1, -4], &[0, 1, 0, 7], &[1, 0, 1, 9]])),
+        Ok(16)
+    );
+    assert_eq!(
+        solve_min_integer_sum(&matrix(&[&[1, -1, 1, 2], &[0, 1, 1, 4]])),
+        Ok(4)
+    );
+    // non-integer pivot values
+    assert_eq!(
+        solve_min_integer_sum(&matrix(&[&[2, 1, 7], &[0, 1, 1]])),
+        Ok(4)
+    );
+    assert_eq!(
+        solve_min_integer_sum(&matrix(&[&[2, 7]])),
+        Err("no non-negative integer solution")
+    );
+    assert_eq!(
+        solve_min_integer_sum(&matrix(&[&[1, 1, 1], &[1, 1, 2]])),
+        Err("no solution")
+    );
+    assert_eq!(
+        solve_min_integer_sum(&matrix(&[&[1, 1, -1]])),
+        Err("no non-negative integer solution")
+    );
+    assert_eq!(solve_min_integer_sum(&matrix(&[&[1, -1, 0]])), Ok(0));
+    assert_eq!(solve_min_integer_sum(&[]), Err("no equations"));
+    assert_eq!(solve(&[vec![]]), Err("invalid augmented matrix"));
+    // no equation bounds the free variables: x1 + 2x2 = x0 + 5
+    assert_eq!(solve_min_integer_sum(&matrix(&[&[1, -1, -2, -5]])), Ok(3));
+    assert_eq!(
+        solve_min_integer_sum(&matrix(&[&[2, -2, 1]])),
+        Err("no non-negative integer solution")
+    );
+    // infeasible with unbounded free variables up to a huge known solution size
+    assert_eq!(
+        min_integer_sum(&matrix(&[&[2, -2, 0, 1], &[0, 1, -100, 0]]), 100_000),
+        Err("search limit exceeded")
+    );
+    let huge = i128::MAX / 3;
+    assert_eq!(
+        solve_min_integer_sum(&matrix(&[&[huge, 0, 1, huge], &[0, huge - 1, 1, huge]])),
+        Err("overflow in exact arithmetic")
+    );
+}
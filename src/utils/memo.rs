@@ -0,0 +1,87 @@
+//! Memoisation of recursive functions
+//!
+//! The recursive function gets a callback instead of calling itself, so the
+//! cache and its statistics stay out of the puzzle logic:
+//!
+//! ```ignore
+//! let mut memo = Memo::new();
+//! let fib = memo.get(90, &|fib, n: u64| if n < 2 { n } else { fib(n - 1) + fib(n - 2) });
+//! ```
+
+use std::{collections::HashMap, fmt, hash::Hash};
+
+/// Cache of computed values with hit/miss counters
+#[derive(Clone, Debug)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self {
+            cache: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Cached value of `f(key)`, `f` recurses through the function it is given
+    pub fn get<F>(&mut self, key: K, f: &F) -> V
+    where
+        F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+    {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+        self.misses += 1;
+        let value = f(&mut |key| self.get(key, f), key.clone());
+        self.cache.insert(key, value.clone());
+        value
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Cache statistics, e.g. for `trace::debug!("{memo}")`
+impl<K, V> fmt::Display for Memo<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lookups = self.hits + self.misses;
+        let rate = if lookups == 0 { 0.0 } else { 100.0 * self.hits as f64 / lookups as f64 };
+        write!(
+            f,
+            "{} entries, {} hits, {} misses ({rate:.1}% hit rate)",
+            self.cache.len(),
+            self.hits,
+            self.misses
+        )
+    }
+}
+
+#[test]
+pub fn test() {
+    let fib = |fib: &mut dyn FnMut(u64) -> u64, n: u64| if n < 2 { n } else { fib(n - 1) + fib(n - 2) };
+    let mut memo = Memo::new();
+    assert_eq!(memo.get(90, &fib), 2880067194370816120);
+    assert_eq!(memo.to_string(), "91 entries, 88 hits, 91 misses (49.2% hit rate)");
+    assert_eq!(memo.get(50, &fib), 12586269025);
+    assert_eq!(memo.to_string(), "91 entries, 89 hits, 91 misses (49.4% hit rate)");
+
+    // captured environment and borrowed keys
+    let words = ["a", "ab", "b", "ba"];
+    let mut memo = Memo::<&str, u64>::new();
+    let ways = memo.get("abab", &|ways, s| {
+        if s.is_empty() {
+            return 1;
+        }
+        words.iter().filter_map(|w| s.strip_prefix(w)).map(&mut *ways).sum()
+    });
+    assert_eq!(ways, 5);
+    assert_eq!(Memo::<u8, u8>::new().to_string(), "0 entries, 0 hits, 0 misses (0.0% hit rate)");
+}
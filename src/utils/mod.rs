@@ -0,0 +1,53 @@
+//! Collection of shared utility functions
+
+pub mod bitset;
+pub mod cuboid;
+pub mod graph;
+pub mod hash;
+pub mod interval;
+pub mod linalg;
+pub mod memo;
+pub mod num;
+pub mod parallel;
+pub mod union_find;
+
+/// Greatest Common Divisor (GCD)
+pub fn gcd<T>(a: T, b: T) -> T
+where
+    T: Copy + Default + PartialEq,
+    T: std::ops::Rem<Output = T>,
+{
+    if b == T::default() {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Least Common Multiple (LCM), 1 for no values
+pub fn lcm<T>(vals: &[T]) -> T
+where
+    T: Copy + Default + PartialEq + From<u8>,
+    T: std::ops::Div<Output = T>,
+    T: std::ops::Mul<Output = T>,
+    T: std::ops::Rem<Output = T>,
+{
+    // dividing first keeps intermediate values as small as the result
+    vals.iter().fold(T::from(1), |a, &b| a / gcd(a, b) * b)
+}
+
+/// Deterministic pseudo-random number generator (xorshift64*)
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+}
@@ -0,0 +1,113 @@
+//! Number theory on `u64` values, with `u128` intermediates to avoid overflows
+
+use std::collections::HashMap;
+
+/// Extended Euclidean algorithm, returns `(g, x, y)` with `a * x + b * y = g = gcd(a, b)`
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - a / b * y)
+    }
+}
+
+/// Inverse of `a` modulo `m`, if `a` and `m` are coprime
+pub fn mod_inverse(a: u64, m: u64) -> Option<u64> {
+    let (g, x, _) = extended_gcd(a as i128, m as i128);
+    (g == 1).then(|| x.rem_euclid(m as i128) as u64)
+}
+
+pub fn mod_mul(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+/// Modular exponentiation by squaring
+pub fn mod_pow(base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    let mut base = base % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mod_mul(result, base, m);
+        }
+        base = mod_mul(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Chinese Remainder Theorem for congruences `x = residue (mod modulus)`
+///
+/// Moduli need not be coprime. Returns the smallest non-negative solution and
+/// the least common multiple of the moduli, or `None` if the congruences
+/// contradict each other.
+/// https://en.wikipedia.org/wiki/Chinese_remainder_theorem#Generalization_to_non-coprime_moduli
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    congruences.iter().try_fold((0, 1), |(r1, m1), &(r2, m2)| {
+        let (g, p, _) = extended_gcd(m1 as i128, m2 as i128);
+        let diff = (r2 % m2) as i128 - r1 as i128;
+        if diff % g != 0 {
+            return None;
+        }
+        let modulus = m1 as i128 / g * m2 as i128;
+        assert!(modulus <= u64::MAX as i128, "modulus overflow");
+        // r1 + m1 * k with k = diff / g * p (mod m2 / g)
+        let k = (diff / g).rem_euclid(m2 as i128 / g) as u128 * p.rem_euclid(m2 as i128 / g) as u128
+            % (m2 as u128 / g as u128);
+        let residue = (r1 as u128 + m1 as u128 * k) % modulus as u128;
+        Some((residue as u64, modulus as u64))
+    })
+}
+
+/// Smallest `x` with `base^x = target (mod m)` using baby-step giant-step,
+/// `base` and `m` must be coprime
+/// https://en.wikipedia.org/wiki/Baby-step_giant-step
+pub fn discrete_log(base: u64, target: u64, m: u64) -> Option<u64> {
+    let n = m.isqrt() + 1;
+    // baby steps base^j for j < n, keeping the smallest exponent
+    let mut table = HashMap::with_capacity(n as usize);
+    let mut value = 1 % m;
+    for j in 0..n {
+        table.entry(value).or_insert(j);
+        value = mod_mul(value, base, m);
+    }
+    // giant steps target * base^(-i * n)
+    let factor = mod_inverse(mod_pow(base, n, m), m).expect("base and modulus are not coprime");
+    let mut gamma = target % m;
+    for i in 0..n {
+        if let Some(j) = table.get(&gamma) {
+            return Some(i * n + j);
+        }
+        gamma = mod_mul(gamma, factor, m);
+    }
+    None
+}
+
+#[test]
+pub fn test() {
+    assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+    assert_eq!(mod_inverse(3, 11), Some(4));
+    assert_eq!(mod_inverse(6, 9), None);
+    assert_eq!(mod_mul(u64::MAX - 1, u64::MAX - 1, u64::MAX), 1);
+    assert_eq!(mod_pow(4, 13, 497), 445);
+    assert_eq!(mod_pow(5, 0, 1), 0);
+    // Fermat's little theorem for the Mersenne prime 2^61 - 1
+    assert_eq!(mod_pow(3, (1 << 61) - 2, (1 << 61) - 1), 1);
+
+    assert_eq!(crt(&[]), Some((0, 1)));
+    assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+    // non-coprime moduli
+    assert_eq!(crt(&[(2, 6), (8, 10)]), Some((8, 30)));
+    assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+    assert_eq!(crt(&[(0, 12), (0, 18)]), Some((0, 36)));
+    // product of moduli beyond 32 bits
+    let (p, q) = (2_147_483_647, 2_147_483_629);
+    assert_eq!(crt(&[(5, p), (7, q)]).map(|(r, m)| (r % p, r % q, m)), Some((5, 7, p * q)));
+
+    assert_eq!(discrete_log(7, 5764801, 20201227), Some(8));
+    assert_eq!(discrete_log(7, 17807724, 20201227), Some(11));
+    assert_eq!(discrete_log(3, 1, 7), Some(0));
+    assert_eq!(discrete_log(2, 3, 7), None);
+    assert_eq!(discrete_log(2, 1, 7), Some(0));
+    assert_eq!(discrete_log(2, 4, 7), Some(2));
+}
@@ -0,0 +1,89 @@
+//! Parallel map over independent work items
+//!
+//! Items are claimed one at a time by scoped worker threads, results come back
+//! in input order, so solvers stay deterministic. Without the `parallel`
+//! feature or with `--threads 1` everything runs on the calling thread.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// configured number of threads, 0 for available parallelism
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Limits the number of worker threads, 0 to use all available cores
+pub fn set_threads(threads: usize) {
+    THREADS.store(threads, Ordering::Relaxed);
+}
+
+/// number of worker threads used by [`par_map`]
+pub fn threads() -> usize {
+    if !cfg!(feature = "parallel") {
+        return 1;
+    }
+    match THREADS.load(Ordering::Relaxed) {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        threads => threads,
+    }
+}
+
+/// `items.iter().map(f).collect()`, spread over worker threads
+pub fn par_map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    map_with_threads(threads(), items, f)
+}
+
+fn map_with_threads<T, R, F>(threads: usize, items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = threads.min(items.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    // workers claim the next unprocessed item, so uneven work balances out
+    let next = AtomicUsize::new(0);
+    let mut results = std::thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let idx = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(idx) else {
+                            return results;
+                        };
+                        results.push((idx, f(item)));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    results.sort_unstable_by_key(|&(idx, _)| idx);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[test]
+pub fn test() {
+    let items = (0..1000u64).collect::<Vec<_>>();
+    let expected = items.iter().map(|n| n * n).collect::<Vec<_>>();
+    for threads in [1, 2, 3, 8, 2000] {
+        assert_eq!(map_with_threads(threads, &items, |n| n * n), expected);
+    }
+    assert_eq!(map_with_threads(4, &Vec::<u8>::new(), |n| *n), vec![]);
+    assert_eq!(par_map(&items, |n| n * n), expected);
+    assert!(threads() >= 1);
+
+    // uneven work per item still comes back in order
+    let sums = map_with_threads(4, &items[..100], |&n| (0..n * 1000).sum::<u64>());
+    assert!(sums.windows(2).all(|w| w[0] < w[1]));
+}
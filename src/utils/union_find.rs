@@ -0,0 +1,119 @@
+//! Disjoint-set forest for grouping elements into connected components
+
+/// Disjoint sets over elements `0..n` with path compression and union by size
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl UnionFind {
+    /// `n` singleton sets
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            components: n,
+        }
+    }
+
+    /// number of elements
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// representative of the set containing `x`
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // point the whole path directly at the root
+        let mut curr = x;
+        while self.parent[curr] != root {
+            curr = std::mem::replace(&mut self.parent[curr], root);
+        }
+        root
+    }
+
+    /// merges the sets of `a` and `b`, false if they already were the same set
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.components -= 1;
+        true
+    }
+
+    /// number of elements in the set containing `x`
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// number of disjoint sets
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// sizes of all disjoint sets, largest first
+    pub fn component_sizes(&self) -> Vec<usize> {
+        let mut sizes = (0..self.len())
+            .filter(|&x| self.parent[x] == x)
+            .map(|root| self.size[root])
+            .collect::<Vec<_>>();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+}
+
+#[test]
+pub fn test() {
+    let mut uf = UnionFind::new(10);
+    assert_eq!(uf.len(), 10);
+    assert_eq!(uf.component_count(), 10);
+    assert!(uf.union(0, 1));
+    assert!(uf.union(2, 3));
+    assert!(uf.union(1, 3));
+    assert!(!uf.union(0, 2));
+    assert!(uf.union(7, 8));
+    assert_eq!(uf.find(0), uf.find(3));
+    assert_ne!(uf.find(0), uf.find(7));
+    assert_eq!(uf.size(2), 4);
+    assert_eq!(uf.size(9), 1);
+    assert_eq!(uf.component_count(), 6);
+    assert_eq!(uf.component_sizes(), vec![4, 2, 1, 1, 1, 1]);
+    assert_eq!(UnionFind::new(0).component_sizes(), vec![]);
+
+    // long chain is flattened by a single find
+    let mut uf = UnionFind::new(1000);
+    for i in 1..1000 {
+        uf.union(i, i - 1);
+    }
+    let root = uf.find(999);
+    assert!((0..1000).all(|i| uf.parent[i] == root || uf.find(i) == root));
+    assert_eq!(uf.component_sizes(), vec![1000]);
+
+    // random unions agree with naive labelling
+    let mut rng = crate::utils::Rng::new(46);
+    let mut uf = UnionFind::new(50);
+    let mut labels = (0..50).collect::<Vec<usize>>();
+    for _ in 0..60 {
+        let (a, b) = ((rng.next_u64() % 50) as usize, (rng.next_u64() % 50) as usize);
+        let (la, lb) = (labels[a], labels[b]);
+        assert_eq!(uf.union(a, b), la != lb);
+        labels.iter_mut().filter(|l| **l == lb).for_each(|l| *l = la);
+        let mut distinct = labels.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        assert_eq!(uf.component_count(), distinct.len());
+        assert_eq!(uf.size(a), labels.iter().filter(|&&l| l == la).count());
+    }
+}
@@ -0,0 +1,420 @@
+//! Export of frames as PNG sequence or animated GIF
+//!
+//! Both encoders are minimal and dependency free: PNG data is compressed with
+//! fixed Huffman codes, only matching runs and repeated scanlines, while GIF
+//! frames use plain LZW. Frame captions are not rendered.
+
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write as _},
+    path::PathBuf,
+};
+
+use super::{Colour, Frame, FrameSink};
+
+/// Edge length of a cell in pixels
+const SCALE: usize = 4;
+
+/// Writes frames to a `.gif` file, or numbered PNG files in a directory
+///
+/// Frames are written as they arrive. The screen size of an animation is that
+/// of its first frame, larger frames are cropped to it.
+pub struct Export {
+    path: PathBuf,
+    delay: u16,
+    gif: Option<GifWriter>,
+    count: usize,
+}
+
+/// Animation file with the header already written
+struct GifWriter {
+    out: BufWriter<File>,
+    width: usize,
+    height: usize,
+}
+
+impl Export {
+    pub fn new(path: impl Into<PathBuf>, fps: u32) -> Self {
+        Self {
+            path: path.into(),
+            delay: (100 / fps.clamp(1, 100)) as u16,
+            gif: None,
+            count: 0,
+        }
+    }
+
+    fn is_gif(&self) -> bool {
+        self.path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("gif"))
+    }
+}
+
+impl FrameSink for Export {
+    fn frame(&mut self, frame: &Frame) {
+        let image = Image::from(frame);
+        if self.is_gif() {
+            let gif = self.gif.get_or_insert_with(|| {
+                let file = File::create(&self.path).expect("failed to create animation");
+                let mut out = BufWriter::new(file);
+                out.write_all(&gif_header(image.width, image.height))
+                    .expect("failed to write animation");
+                GifWriter {
+                    out,
+                    width: image.width,
+                    height: image.height,
+                }
+            });
+            let image = image.crop(gif.width, gif.height);
+            gif.out
+                .write_all(&gif_frame(&image, self.delay))
+                .expect("failed to write animation");
+        } else {
+            if self.count == 0 {
+                fs::create_dir_all(&self.path).expect("failed to create frame directory");
+            }
+            let path = self.path.join(format!("frame_{:05}.png", self.count));
+            fs::write(path, encode_png(&image)).expect("failed to write frame");
+        }
+        self.count += 1;
+    }
+
+    fn finish(&mut self) {
+        if let Some(mut gif) = self.gif.take() {
+            gif.out
+                .write_all(&[GIF_TRAILER])
+                .and_then(|_| gif.out.flush())
+                .expect("failed to write animation");
+        }
+        if self.count > 0 {
+            println!("{} frames exported to {}.", self.count, self.path.display());
+        }
+    }
+}
+
+/// Frame rendered to palette indices
+struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl From<&Frame> for Image {
+    fn from(frame: &Frame) -> Self {
+        let width = frame.width * SCALE;
+        let mut pixels = Vec::with_capacity(width * frame.height * SCALE);
+        for row in frame.rows() {
+            let line = row
+                .iter()
+                .flat_map(|&cell| [palette_index(cell); SCALE])
+                .collect::<Vec<_>>();
+            for _ in 0..SCALE {
+                pixels.extend_from_slice(&line);
+            }
+        }
+        Self {
+            width,
+            height: frame.height * SCALE,
+            pixels,
+        }
+    }
+}
+
+impl Image {
+    /// Top left part of at most the given size
+    fn crop(self, width: usize, height: usize) -> Self {
+        if self.width <= width && self.height <= height {
+            return self;
+        }
+        let (width, height) = (self.width.min(width), self.height.min(height));
+        let pixels = self
+            .pixels
+            .chunks(self.width.max(1))
+            .take(height)
+            .flat_map(|line| &line[..width])
+            .copied()
+            .collect();
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+fn palette_index(cell: u8) -> u8 {
+    let colour = Colour::of(cell);
+    Colour::ALL.iter().position(|&c| c == colour).unwrap() as u8
+}
+
+/// Colour table padded to a power of two with at least 16 entries
+fn palette() -> Vec<u8> {
+    let mut palette = Colour::ALL.iter().flat_map(|c| c.rgb()).collect::<Vec<_>>();
+    palette.resize(3 << PALETTE_BITS, 0);
+    palette
+}
+
+const PALETTE_BITS: u8 = 4;
+
+/// Collects bits in least significant bit first order
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u8) {
+        self.acc |= value << self.bits;
+        self.bits += bits;
+        while self.bits >= 8 {
+            self.bytes.push(self.acc as u8);
+            self.acc >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    /// writes a Huffman code, which is packed most significant bit first
+    fn write_code(&mut self, code: u32, bits: u8) {
+        self.write(code.reverse_bits() >> (32 - bits), bits);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.acc as u8);
+        }
+        self.bytes
+    }
+}
+
+fn encode_png(image: &Image) -> Vec<u8> {
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+
+    let mut header = Vec::with_capacity(13);
+    header.extend((image.width as u32).to_be_bytes());
+    header.extend((image.height as u32).to_be_bytes());
+    header.extend([8, 3, 0, 0, 0]); // 8 bit palette indices
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"PLTE", &palette());
+
+    // each scanline is prefixed with filter type 0
+    let mut raw = Vec::with_capacity((image.width + 1) * image.height);
+    for line in image.pixels.chunks(image.width.max(1)) {
+        raw.push(0);
+        raw.extend_from_slice(line);
+    }
+    write_chunk(&mut png, b"IDAT", &zlib(&raw, image.width + 1));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    png.extend(kind);
+    png.extend(data);
+    png.extend(crc32(kind.iter().chain(data)).to_be_bytes());
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut c = i as u32;
+            let mut k = 0;
+            while k < 8 {
+                c = if c & 1 != 0 { 0xedb88320 ^ (c >> 1) } else { c >> 1 };
+                k += 1;
+            }
+            table[i] = c;
+            i += 1;
+        }
+        table
+    };
+    !bytes.into_iter().fold(!0u32, |crc, &b| {
+        TABLE[((crc ^ b as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (a, b) = bytes.iter().fold((1u32, 0u32), |(a, b), &byte| {
+        let a = (a + byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    b << 16 | a
+}
+
+/// Compresses data into a zlib stream with a single fixed Huffman block,
+/// matching repetitions of the previous byte or of the previous `stride` bytes
+fn zlib(data: &[u8], stride: usize) -> Vec<u8> {
+    const LENGTH_BASE: [u16; 29] = [
+        3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115,
+        131, 163, 195, 227, 258,
+    ];
+    const LENGTH_EXTRA: [u8; 29] = [
+        0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+    ];
+    const DIST_BASE: [u16; 30] = [
+        1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+        2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+    ];
+    const DIST_EXTRA: [u8; 30] = [
+        0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12,
+        13, 13,
+    ];
+
+    fn write_symbol(out: &mut BitWriter, symbol: u16) {
+        match symbol {
+            0..=143 => out.write_code(0x30 + symbol as u32, 8),
+            144..=255 => out.write_code(0x190 + symbol as u32 - 144, 9),
+            256..=279 => out.write_code(symbol as u32 - 256, 7),
+            _ => out.write_code(0xc0 + symbol as u32 - 280, 8),
+        }
+    }
+
+    let match_len = |i: usize, dist: usize| {
+        (0..258.min(data.len() - i))
+            .take_while(|&k| data[i + k] == data[i + k - dist])
+            .count()
+    };
+
+    let mut out = BitWriter::default();
+    out.write(1, 1); // final block
+    out.write(1, 2); // fixed Huffman codes
+    let mut i = 0;
+    while i < data.len() {
+        let candidates = [1, stride].into_iter().filter(|&d| d > 0 && d <= i && d <= 32768);
+        let best = candidates.map(|d| (match_len(i, d), d)).max_by_key(|&(len, _)| len);
+        match best {
+            Some((len, dist)) if len >= 3 => {
+                let code = LENGTH_BASE.iter().rposition(|&b| b as usize <= len).unwrap();
+                write_symbol(&mut out, 257 + code as u16);
+                out.write((len - LENGTH_BASE[code] as usize) as u32, LENGTH_EXTRA[code]);
+                let code = DIST_BASE.iter().rposition(|&b| b as usize <= dist).unwrap();
+                out.write_code(code as u32, 5);
+                out.write((dist - DIST_BASE[code] as usize) as u32, DIST_EXTRA[code]);
+                i += len;
+            }
+            _ => {
+                write_symbol(&mut out, data[i] as u16);
+                i += 1;
+            }
+        }
+    }
+    write_symbol(&mut out, 256); // end of block
+
+    let mut zlib = vec![0x78, 0x01];
+    zlib.extend(out.finish());
+    zlib.extend(adler32(data).to_be_bytes());
+    zlib
+}
+
+const GIF_TRAILER: u8 = 0x3b;
+
+/// Signature, logical screen with global colour table and looping extension
+fn gif_header(width: usize, height: usize) -> Vec<u8> {
+    let mut gif = b"GIF89a".to_vec();
+    gif.extend((width as u16).to_le_bytes());
+    gif.extend((height as u16).to_le_bytes());
+    gif.extend([0xf0 | (PALETTE_BITS - 1), 0, 0]); // global colour table
+    gif.extend(palette());
+    // loop indefinitely
+    gif.extend(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+    gif
+}
+
+/// Image block of an animation frame shown for `delay` hundredths of a second
+fn gif_frame(image: &Image, delay: u16) -> Vec<u8> {
+    // graphic control extension: clear to background, frame delay
+    let mut gif = vec![0x21, 0xf9, 0x04, 0x08];
+    gif.extend(delay.to_le_bytes());
+    gif.extend([0, 0]);
+
+    gif.push(0x2c);
+    gif.extend([0, 0, 0, 0]);
+    gif.extend((image.width as u16).to_le_bytes());
+    gif.extend((image.height as u16).to_le_bytes());
+    gif.push(0);
+
+    gif.push(PALETTE_BITS);
+    for block in lzw(&image.pixels, PALETTE_BITS).chunks(255) {
+        gif.push(block.len() as u8);
+        gif.extend(block);
+    }
+    gif.push(0);
+    gif
+}
+
+/// Variable code width LZW compression as used by GIF
+fn lzw(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    const MAX_CODES: u16 = 4096;
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+
+    let mut out = BitWriter::default();
+    let mut dict = std::collections::HashMap::<(u16, u8), u16>::new();
+    let mut next = end + 1;
+    let mut code_size = min_code_size + 1;
+    out.write(clear as u32, code_size);
+
+    let mut prefix = None;
+    for &index in indices {
+        let Some(p) = prefix else {
+            prefix = Some(index as u16);
+            continue;
+        };
+        if let Some(&code) = dict.get(&(p, index)) {
+            prefix = Some(code);
+            continue;
+        }
+        out.write(p as u32, code_size);
+        // the decoder adds entries one code later, so grow before inserting
+        if next == 1 << code_size && code_size < 12 {
+            code_size += 1;
+        }
+        if next < MAX_CODES {
+            dict.insert((p, index), next);
+            next += 1;
+        } else {
+            out.write(clear as u32, code_size);
+            dict.clear();
+            next = end + 1;
+            code_size = min_code_size + 1;
+        }
+        prefix = Some(index as u16);
+    }
+    if let Some(p) = prefix {
+        out.write(p as u32, code_size);
+        if next == 1 << code_size && code_size < 12 {
+            code_size += 1;
+        }
+    }
+    out.write(end as u32, code_size);
+    out.finish()
+}
+
+#[test]
+pub fn test() {
+    assert_eq!(crc32(b"IEND"), 0xae426082);
+    assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+
+    let frame = Frame::from_rows(["#.O", "@.."].iter().map(|s| s.bytes()));
+    let image = Image::from(&frame);
+    assert_eq!((image.width, image.height), (3 * SCALE, 2 * SCALE));
+    assert_eq!(image.pixels[0], palette_index(b'#'));
+    assert_eq!(image.pixels[image.width * SCALE], palette_index(b'@'));
+
+    let png = encode_png(&image);
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+
+    let gif = gif_header(image.width, image.height);
+    assert!(gif.starts_with(b"GIF89a"));
+    assert_eq!(gif[6..10], [12, 0, 8, 0]);
+    let block = gif_frame(&image, 10);
+    assert_eq!(block[..4], [0x21, 0xf9, 0x04, 0x08]);
+    assert_eq!(block.last(), Some(&0));
+
+    let cropped = image.crop(2 * SCALE, 10 * SCALE);
+    assert_eq!((cropped.width, cropped.height), (2 * SCALE, 2 * SCALE));
+    assert_eq!(cropped.pixels[cropped.width * SCALE], palette_index(b'@'));
+}
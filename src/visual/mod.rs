@@ -0,0 +1,276 @@
+//! Visualisation of grid simulations
+//!
+//! Solvers describe their state as a [`Frame`] of ASCII cells and hand it to
+//! [`emit`]. Frames are only built when a [`FrameSink`] has been installed, so
+//! the hook costs a single atomic load when visualisation is disabled.
+
+use std::{
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+mod image;
+
+pub use image::Export;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SINKS: Mutex<Vec<Box<dyn FrameSink>>> = Mutex::new(Vec::new());
+
+/// Snapshot of a simulation as a grid of ASCII cells
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<u8>,
+    caption: String,
+}
+
+impl Frame {
+    /// Creates an empty frame filled with `.` cells
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![b'.'; width * height],
+            caption: String::new(),
+        }
+    }
+
+    /// Creates a frame from rows of cells, padding short rows with spaces
+    pub fn from_rows<R, I>(rows: R) -> Self
+    where
+        R: IntoIterator<Item = I>,
+        I: IntoIterator<Item = u8>,
+    {
+        let rows = rows
+            .into_iter()
+            .map(|row| row.into_iter().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+        let mut frame = Self::new(width, rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            frame.cells[y * width..(y + 1) * width].fill(b' ');
+            frame.cells[y * width..y * width + row.len()].copy_from_slice(row);
+        }
+        frame
+    }
+
+    /// Adds a line of text displayed below the grid
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = caption.into();
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn caption(&self) -> &str {
+        &self.caption
+    }
+
+    /// Sets a cell, ignoring coordinates outside the frame
+    pub fn set(&mut self, x: usize, y: usize, cell: u8) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = cell;
+        }
+    }
+
+    /// Iterates over the rows of cells
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+}
+
+/// Consumer of frames emitted by solvers
+pub trait FrameSink: Send {
+    /// Receives the next frame of the simulation
+    fn frame(&mut self, frame: &Frame);
+
+    /// Called once solving has completed
+    fn finish(&mut self) {}
+}
+
+/// Installs a sink receiving all subsequently emitted frames
+pub fn install(sink: Box<dyn FrameSink>) {
+    SINKS.lock().unwrap().push(sink);
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Flushes and removes all installed sinks, disabling visualisation
+pub fn finish() {
+    ENABLED.store(false, Ordering::Relaxed);
+    for mut sink in SINKS.lock().unwrap().drain(..) {
+        sink.finish();
+    }
+}
+
+/// Whether any sink is installed, i.e. frames should be produced
+#[inline]
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Passes the frame built by `f` to the installed sinks, if any
+#[inline]
+pub fn emit<F: FnOnce() -> Frame>(f: F) {
+    if enabled() {
+        emit_frame(&f());
+    }
+}
+
+#[cold]
+fn emit_frame(frame: &Frame) {
+    for sink in SINKS.lock().unwrap().iter_mut() {
+        sink.frame(frame);
+    }
+}
+
+/// Renders frames to the terminal using ANSI escape codes
+pub struct Terminal {
+    delay: Duration,
+    frames: usize,
+}
+
+impl Terminal {
+    pub fn new(fps: u32) -> Self {
+        Self {
+            delay: Duration::from_secs_f64(1.0 / fps.max(1) as f64),
+            frames: 0,
+        }
+    }
+}
+
+impl FrameSink for Terminal {
+    fn frame(&mut self, frame: &Frame) {
+        let mut out = String::with_capacity((frame.width() * 6 + 1) * frame.height());
+        // clear screen once, then just move cursor to top left
+        out.push_str(if self.frames == 0 { "\x1b[2J\x1b[H" } else { "\x1b[H" });
+        for row in frame.rows() {
+            let mut colour = None;
+            for &cell in row {
+                let c = Colour::of(cell);
+                if Some(c) != colour {
+                    out.push_str(c.ansi());
+                    colour = Some(c);
+                }
+                out.push(cell as char);
+            }
+            out.push_str("\x1b[0m\x1b[K\n");
+        }
+        out.push_str(&format!("\x1b[K{}\n", frame.caption()));
+
+        let mut stdout = std::io::stdout().lock();
+        let _ = stdout.write_all(out.as_bytes());
+        let _ = stdout.flush();
+        drop(stdout);
+
+        self.frames += 1;
+        thread::sleep(self.delay);
+    }
+
+    fn finish(&mut self) {
+        if self.frames > 0 {
+            println!("\x1b[0m{} frames rendered.", self.frames);
+        }
+    }
+}
+
+/// Colour classes of cell symbols
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Colour {
+    Default,
+    Wall,
+    Empty,
+    Box,
+    Actor,
+    Seat,
+    Direction,
+    Mirror,
+    Count,
+}
+
+impl Colour {
+    /// All colours, ordered by palette index
+    const ALL: [Colour; 9] = [
+        Colour::Default,
+        Colour::Wall,
+        Colour::Empty,
+        Colour::Box,
+        Colour::Actor,
+        Colour::Seat,
+        Colour::Direction,
+        Colour::Mirror,
+        Colour::Count,
+    ];
+
+    fn of(cell: u8) -> Self {
+        match cell {
+            b'#' => Colour::Wall,
+            b'.' | b' ' => Colour::Empty,
+            b'O' | b'[' | b']' => Colour::Box,
+            b'@' | b'E' | b'S' => Colour::Actor,
+            b'L' => Colour::Seat,
+            b'>' | b'<' | b'^' | b'v' => Colour::Direction,
+            b'|' | b'-' | b'/' | b'\\' => Colour::Mirror,
+            b'0'..=b'9' => Colour::Count,
+            _ => Colour::Default,
+        }
+    }
+
+    /// ANSI escape sequence selecting the colour
+    fn ansi(self) -> &'static str {
+        match self {
+            Colour::Default => "\x1b[0m",
+            Colour::Wall => "\x1b[0;90m",
+            Colour::Empty => "\x1b[0;2m",
+            Colour::Box => "\x1b[0;33m",
+            Colour::Actor => "\x1b[1;31m",
+            Colour::Seat => "\x1b[0;32m",
+            Colour::Direction => "\x1b[0;36m",
+            Colour::Mirror => "\x1b[0;34m",
+            Colour::Count => "\x1b[1;36m",
+        }
+    }
+
+    /// RGB value used for image export
+    fn rgb(self) -> [u8; 3] {
+        match self {
+            Colour::Default => [0xe0, 0xe0, 0xe0],
+            Colour::Wall => [0x60, 0x60, 0x60],
+            Colour::Empty => [0x10, 0x10, 0x1a],
+            Colour::Box => [0xe0, 0xb0, 0x20],
+            Colour::Actor => [0xf0, 0x30, 0x30],
+            Colour::Seat => [0x30, 0xc0, 0x50],
+            Colour::Direction => [0x30, 0xb0, 0xc0],
+            Colour::Mirror => [0x40, 0x60, 0xe0],
+            Colour::Count => [0x80, 0xe0, 0xf0],
+        }
+    }
+}
+
+#[test]
+pub fn test() {
+    let frame = Frame::from_rows(["#.O", "@"].iter().map(|s| s.bytes())).with_caption("t=0");
+    assert_eq!(frame.width(), 3);
+    assert_eq!(frame.height(), 2);
+    assert_eq!(frame.rows().collect::<Vec<_>>(), vec![&b"#.O"[..], &b"@  "[..]]);
+    assert_eq!(frame.caption(), "t=0");
+
+    let mut frame = Frame::new(2, 2);
+    frame.set(1, 1, b'#');
+    frame.set(5, 5, b'#');
+    assert_eq!(frame.rows().collect::<Vec<_>>(), vec![&b".."[..], &b".#"[..]]);
+
+    assert!(!enabled());
+    emit(|| unreachable!("frame built while disabled"));
+}
@@ -0,0 +1,321 @@
+//! Virtual machine core of the puzzle computers
+//!
+//! Days with an assembly language implement [`Instruction`] (and [`Decode`])
+//! for their instruction set and run the program on a [`Machine`], which takes
+//! care of the instruction pointer, cycle counting, breakpoints, loop
+//! detection and tracing of executed instructions (`-vv --trace-filter vm`).
+//! Programs are listed as pseudo-code with [`disassemble`].
+
+use std::fmt::{self, Write as _};
+
+use crate::trace;
+
+/// Instruction of a machine with registers and I/O `State`
+///
+/// The `Display` form is used in traces, so it should read like pseudo-code.
+pub trait Instruction: fmt::Display {
+    type State: fmt::Debug;
+
+    /// applies the instruction to the machine state
+    fn execute(&self, state: &mut Self::State) -> Flow;
+
+    /// number of cycles the instruction takes to complete
+    fn cycles(&self) -> usize {
+        1
+    }
+
+    /// possible addresses of the next instruction, for control flow analysis
+    fn successors(&self, ip: usize) -> Vec<usize> {
+        vec![ip + 1]
+    }
+
+    /// whether the instruction starts a section of the listing (besides jump targets)
+    fn starts_block(&self) -> bool {
+        false
+    }
+}
+
+/// Decoding of an instruction from its encoded form `T`, e.g. a line of input
+pub trait Decode<T>: Sized {
+    fn decode(code: T) -> Self;
+}
+
+/// How execution continues after an instruction
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Flow {
+    /// next instruction
+    Next,
+    /// relative jump
+    Jump(isize),
+    /// absolute jump
+    Goto(usize),
+    Halt,
+}
+
+/// Reason the machine stopped running
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Exit {
+    /// instruction pointer left the program or an instruction halted
+    Halted,
+    Breakpoint,
+    /// an instruction was about to be executed a second time
+    Loop,
+    /// stop condition of [`Machine::run_until`] was met
+    Condition,
+}
+
+/// Machine executing a program of instructions `I`
+pub struct Machine<'a, I: Instruction> {
+    pub program: &'a [I],
+    pub state: I::State,
+    pub ip: usize,
+    pub cycle: usize,
+    /// cycles already spent on the current instruction
+    progress: usize,
+    halted: bool,
+    breakpoints: Vec<usize>,
+}
+
+impl<'a, I: Instruction> Machine<'a, I> {
+    pub fn new(program: &'a [I], state: I::State) -> Self {
+        Self {
+            program,
+            state,
+            ip: 0,
+            cycle: 0,
+            progress: 0,
+            halted: false,
+            breakpoints: Vec::new(),
+        }
+    }
+
+    /// Restarts the program with new state, keeping breakpoints
+    pub fn reset(&mut self, state: I::State) {
+        self.state = state;
+        self.ip = 0;
+        self.cycle = 0;
+        self.progress = 0;
+        self.halted = false;
+    }
+
+    /// Stops running before the instruction at `ip` is executed
+    pub fn add_breakpoint(&mut self, ip: usize) {
+        self.breakpoints.push(ip);
+    }
+
+    pub fn halted(&self) -> bool {
+        self.halted || self.ip >= self.program.len()
+    }
+
+    /// Executes the current instruction, returns false if already halted
+    pub fn step(&mut self) -> bool {
+        if self.halted() {
+            return false;
+        }
+        let program = self.program;
+        let instruction = &program[self.ip];
+        self.cycle += instruction.cycles() - self.progress;
+        self.execute(instruction);
+        true
+    }
+
+    /// Advances by a single cycle, returns false if already halted
+    ///
+    /// The current instruction takes effect in its last cycle.
+    pub fn tick(&mut self) -> bool {
+        if self.halted() {
+            return false;
+        }
+        let program = self.program;
+        let instruction = &program[self.ip];
+        self.cycle += 1;
+        self.progress += 1;
+        if self.progress >= instruction.cycles() {
+            self.execute(instruction);
+        }
+        true
+    }
+
+    fn execute(&mut self, instruction: &I) {
+        self.progress = 0;
+        let flow = instruction.execute(&mut self.state);
+        trace::trace!("{:>4}: {:<20} {:?}", self.ip, instruction.to_string(), self.state);
+        match flow {
+            Flow::Next => self.ip += 1,
+            Flow::Jump(offset) => self.ip = self.ip.wrapping_add_signed(offset),
+            Flow::Goto(ip) => self.ip = ip,
+            Flow::Halt => self.halted = true,
+        }
+    }
+
+    /// Runs until the program halts or a breakpoint is reached
+    pub fn run(&mut self) -> Exit {
+        self.run_until(|_| false)
+    }
+
+    /// Runs until `condition` holds after an instruction, the program halts or
+    /// a breakpoint is reached
+    pub fn run_until(&mut self, mut condition: impl FnMut(&Self) -> bool) -> Exit {
+        loop {
+            self.step();
+            if self.halted() {
+                return Exit::Halted;
+            }
+            if self.breakpoints.contains(&self.ip) {
+                return Exit::Breakpoint;
+            }
+            if condition(self) {
+                return Exit::Condition;
+            }
+        }
+    }
+
+    /// Runs until an instruction is about to be executed a second time
+    pub fn run_until_loop(&mut self) -> Exit {
+        let mut visited = vec![false; self.program.len()];
+        if let Some(first) = visited.get_mut(self.ip) {
+            *first = true;
+        }
+        match self.run_until(|vm| std::mem::replace(&mut visited[vm.ip], true)) {
+            Exit::Condition => Exit::Loop,
+            exit => exit,
+        }
+    }
+}
+
+/// Listing of a program split into basic blocks, with annotated jumps and loops
+pub fn disassemble<I: Instruction>(program: &[I]) -> String {
+    let len = program.len();
+    let mut leaders = vec![false; len + 1];
+    let mut back_edges = vec![Vec::new(); len];
+    leaders[0] = true;
+    for (ip, instruction) in program.iter().enumerate() {
+        leaders[ip] |= instruction.starts_block();
+        let successors = instruction.successors(ip);
+        if successors != [ip + 1] {
+            leaders[ip + 1] = true;
+            for &target in successors.iter().filter(|&&target| target < len) {
+                leaders[target] = true;
+                if target <= ip {
+                    back_edges[target].push(ip);
+                }
+            }
+        }
+    }
+
+    let mut listing = String::new();
+    for (ip, instruction) in program.iter().enumerate() {
+        if leaders[ip] {
+            if ip > 0 {
+                listing.push('\n');
+            }
+            match back_edges[ip].as_slice() {
+                [] => writeln!(listing, "block {ip}:"),
+                sources => writeln!(listing, "block {ip}: loop from {}", join(sources)),
+            }
+            .unwrap();
+        }
+        let line = format!("{ip:>4}: {instruction}");
+        let successors = instruction.successors(ip);
+        if successors == [ip + 1] {
+            writeln!(listing, "{line}").unwrap();
+        } else {
+            let targets = successors.iter().map(|&target| {
+                if target < len { target.to_string() } else { "end".to_owned() }
+            });
+            writeln!(listing, "{line:<32} -> {}", targets.collect::<Vec<_>>().join(", ")).unwrap();
+        }
+    }
+    listing
+}
+
+fn join(ips: &[usize]) -> String {
+    ips.iter().map(|ip| ip.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+#[test]
+pub fn test() {
+    /// counter machine: `inc` takes two cycles, `jmp` is relative
+    enum Op {
+        Inc,
+        Jmp(isize),
+        Hlt,
+    }
+
+    impl fmt::Display for Op {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Op::Inc => write!(f, "n += 1"),
+                Op::Jmp(offset) => write!(f, "goto {offset:+}"),
+                Op::Hlt => write!(f, "halt"),
+            }
+        }
+    }
+
+    impl Instruction for Op {
+        type State = usize;
+
+        fn execute(&self, n: &mut usize) -> Flow {
+            match self {
+                Op::Inc => {
+                    *n += 1;
+                    Flow::Next
+                }
+                Op::Jmp(offset) => Flow::Jump(*offset),
+                Op::Hlt => Flow::Halt,
+            }
+        }
+
+        fn cycles(&self) -> usize {
+            if let Op::Inc = self { 2 } else { 1 }
+        }
+
+        fn successors(&self, ip: usize) -> Vec<usize> {
+            match self {
+                Op::Inc => vec![ip + 1],
+                Op::Jmp(offset) => vec![ip.wrapping_add_signed(*offset)],
+                Op::Hlt => Vec::new(),
+            }
+        }
+    }
+
+    let program = [Op::Inc, Op::Inc, Op::Hlt, Op::Inc];
+    let mut vm = Machine::new(&program, 0);
+    assert_eq!(vm.run(), Exit::Halted);
+    assert_eq!((vm.state, vm.ip, vm.cycle), (2, 2, 5));
+    assert!(!vm.step());
+
+    vm.reset(0);
+    assert!(vm.tick());
+    assert_eq!((vm.state, vm.ip), (0, 0));
+    assert!(vm.tick());
+    assert_eq!((vm.state, vm.ip), (1, 1));
+
+    let program = [Op::Inc, Op::Inc, Op::Jmp(-1)];
+    let mut vm = Machine::new(&program, 0);
+    assert_eq!(vm.run_until_loop(), Exit::Loop);
+    assert_eq!((vm.state, vm.ip), (2, 1));
+
+    vm.reset(0);
+    vm.add_breakpoint(2);
+    assert_eq!(vm.run(), Exit::Breakpoint);
+    assert_eq!((vm.state, vm.cycle), (2, 4));
+
+    let mut vm = Machine::new(&program, 0);
+    assert_eq!(vm.run_until(|vm| vm.state == 5), Exit::Condition);
+    assert_eq!(vm.cycle, 13);
+
+    assert_eq!(
+        disassemble(&program),
+        [
+            "block 0:",
+            "   0: n += 1",
+            "",
+            "block 1: loop from 2",
+            "   1: n += 1",
+            "   2: goto -1                    -> 1",
+            "",
+        ]
+        .join("\n")
+    );
+}
@@ -1,5 +1,9 @@
 //! Day 8: Handheld Halting
 
+use std::fmt;
+
+use crate::vm::{self, Decode, Exit, Flow, Machine};
+
 /// accumulator value before any instruction is executed a second time
 pub fn a(input: &Vec<&str>) -> String {
     let program = parse_input(input);
@@ -24,42 +28,21 @@ pub fn b(input: &Vec<&str>) -> String {
     unreachable!("solution not found")
 }
 
+/// program as annotated pseudo-code
+pub fn disassemble(input: &Vec<&str>) -> String {
+    vm::disassemble(&parse_input(input))
+}
+
 fn parse_input(input: &Vec<&str>) -> Vec<Instruction> {
-    input
-        .iter()
-        .map(|s| s.split_once(' ').unwrap())
-        .map(|(op, arg)| Instruction {
-            operation: match op {
-                "acc" => Operation::Acc,
-                "jmp" => Operation::Jmp,
-                "nop" => Operation::Nop,
-                _ => unreachable!("invalid instruction"),
-            },
-            argument: arg.parse().unwrap(),
-        })
-        .collect::<Vec<_>>()
+    input.iter().map(|&line| Instruction::decode(line)).collect()
 }
 
 fn execute(program: &Vec<Instruction>) -> Result<isize, isize> {
-    let mut acc = 0isize;
-    let mut ip = 0usize;
-    let mut covered = vec![false; program.len()];
-    while ip < program.len() {
-        if covered[ip] {
-            return Err(acc);
-        }
-        covered[ip] = true;
-        let inst = &program[ip];
-        match inst.operation {
-            Operation::Acc => {
-                acc += inst.argument;
-                ip += 1;
-            }
-            Operation::Jmp => ip = ((ip as isize) + inst.argument) as usize,
-            Operation::Nop => ip += 1,
-        }
+    let mut vm = Machine::new(program, 0);
+    match vm.run_until_loop() {
+        Exit::Halted => Ok(vm.state),
+        _ => Err(vm.state),
     }
-    Ok(acc)
 }
 
 #[derive(Clone, Copy)]
@@ -75,6 +58,54 @@ struct Instruction {
     argument: isize,
 }
 
+impl Decode<&str> for Instruction {
+    fn decode(line: &str) -> Self {
+        let (op, arg) = line.split_once(' ').unwrap();
+        Instruction {
+            operation: match op {
+                "acc" => Operation::Acc,
+                "jmp" => Operation::Jmp,
+                "nop" => Operation::Nop,
+                _ => unreachable!("invalid instruction"),
+            },
+            argument: arg.parse().unwrap(),
+        }
+    }
+}
+
+impl vm::Instruction for Instruction {
+    /// accumulator
+    type State = isize;
+
+    fn execute(&self, acc: &mut isize) -> Flow {
+        match self.operation {
+            Operation::Acc => {
+                *acc += self.argument;
+                Flow::Next
+            }
+            Operation::Jmp => Flow::Jump(self.argument),
+            Operation::Nop => Flow::Next,
+        }
+    }
+
+    fn successors(&self, ip: usize) -> Vec<usize> {
+        match self.operation {
+            Operation::Jmp => vec![ip.wrapping_add_signed(self.argument)],
+            _ => vec![ip + 1],
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.operation {
+            Operation::Acc => write!(f, "acc += {}", self.argument),
+            Operation::Jmp => write!(f, "goto {:+}", self.argument),
+            Operation::Nop => write!(f, "nop"),
+        }
+    }
+}
+
 #[test]
 pub fn test() {
     let input = vec![
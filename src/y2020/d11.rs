@@ -1,5 +1,7 @@
 //! Day 11: Seating System
 
+use crate::visual::{self, Frame};
+
 /// number of occupied seats at equilibrium, considering adjacency
 pub fn a(input: &Vec<&str>) -> String {
     let mut grid = parse_input(input);
@@ -91,9 +93,20 @@ fn simulate(grid: &mut Grid, visible: bool) -> usize {
         }
     }
     *grid = next_grid;
+    visual::emit(|| render(grid).with_caption(format!("{count} seats changed")));
     count
 }
 
+fn render(grid: &Grid) -> Frame {
+    Frame::from_rows(grid.iter().map(|row| {
+        row.iter().map(|seat| match seat {
+            Seat::None => b'.',
+            Seat::Empty => b'L',
+            Seat::Occupied => b'#',
+        })
+    }))
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum Seat {
     None,
@@ -1,5 +1,7 @@
 //! Day 12: Rain Risk
 
+use crate::trace;
+
 /// Manhattan distance to final position
 pub fn a(input: &Vec<&str>) -> String {
     let instructions = process_input(input);
@@ -53,8 +55,7 @@ fn calculate_position(instructions: &Vec<Instruction>) -> (isize, isize) {
                 position.1 += (sin * *value as f64) as isize;
             }
         }
-        #[cfg(test)]
-        println!("{:?}", position);
+        trace::trace!("{:?}", position);
     }
 
     position
@@ -85,8 +86,7 @@ fn calculate_position_with_waypoint(instructions: &Vec<Instruction>) -> (isize,
                 position.1 += vector.1 * value;
             }
         }
-        #[cfg(test)]
-        println!("{:?}", position);
+        trace::trace!("{:?}", position);
     }
 
     position
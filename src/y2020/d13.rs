@@ -1,5 +1,7 @@
 //! Day 13: Shuttle Search
 
+use crate::utils::num;
+
 /// product of earliest bus ID and waiting time
 pub fn a(input: &Vec<&str>) -> String {
     let (start, buses) = parse_input(input);
@@ -11,7 +13,7 @@ pub fn a(input: &Vec<&str>) -> String {
     (earliest_bus * wait).to_string()
 }
 
-/// part b
+/// earliest timestamp with buses departing at their offsets
 pub fn b(input: &Vec<&str>) -> String {
     let (_, buses) = parse_input(input);
     find_matching_timestamp(&buses).to_string()
@@ -24,23 +26,15 @@ fn parse_input(input: &Vec<&str>) -> (usize, Vec<Option<usize>>) {
     )
 }
 
+/// earliest time when each bus departs as many minutes later as its offset in the list
 fn find_matching_timestamp(buses: &Vec<Option<usize>>) -> usize {
-    let mut time = 0;
-    let mut step = 1;
-
-    for (i, id) in buses
+    let congruences = buses
         .iter()
         .enumerate()
-        .filter_map(|(i, val)| val.map(|id| (i, id)))
-    {
-        while (time + i) % id != 0 {
-            time += step;
-        }
-
-        step *= id; 
-    }
-
-    time
+        .filter_map(|(i, bus)| bus.map(|id| (((id - i % id) % id) as u64, id as u64)))
+        .collect::<Vec<_>>();
+    let (time, _) = num::crt(&congruences).expect("buses never depart in sequence");
+    time as usize
 }
 
 #[test]
@@ -70,5 +64,8 @@ pub fn test() {
         1202161486
     );
 
+    // bus IDs sharing factors
+    assert_eq!(find_matching_timestamp(&vec![Some(4), None, Some(6)]), 4);
+
     assert_eq!(b(&input), "1068781");
 }
@@ -1,5 +1,6 @@
 //! Day 16: Ticket Translation
 
+use crate::trace;
 use std::{collections::BTreeSet, str::FromStr};
 
 /// ticket scanning error rate
@@ -49,7 +50,7 @@ pub fn b(input: &Vec<&str>) -> String {
         if rm.0.starts_with("departure") {
             prod *= notes.own_ticket.numbers[*pos] as usize;
         }
-        println!("{}: #{}", rm.0, pos);
+        trace::debug!("{}: #{}", rm.0, pos);
         found.insert(*pos);
     }
     
@@ -1,12 +1,15 @@
 //! Day 17: Conway Cubes
 
-use std::collections::{HashMap, HashSet};
+use crate::utils::bitset::BitSet;
+
+/// number of cycles to simulate
+const CYCLES: usize = 6;
 
 /// cube configuration after 6 cycles
 pub fn a(input: &Vec<&str>) -> String {
-    let mut cubes = parse_input(input);
-    for _ in 0..6 {
-        simulate(&mut cubes, false);
+    let mut cubes = parse_input(input, false);
+    for _ in 0..CYCLES {
+        simulate(&mut cubes);
     }
 
     cubes.len().to_string()
@@ -14,91 +17,132 @@ pub fn a(input: &Vec<&str>) -> String {
 
 /// hypercube configuration after 6 cycles
 pub fn b(input: &Vec<&str>) -> String {
-    let mut cubes = parse_input(input);
-    for _ in 0..6 {
-        simulate(&mut cubes, true);
+    let mut cubes = parse_input(input, true);
+    for _ in 0..CYCLES {
+        simulate(&mut cubes);
     }
 
     cubes.len().to_string()
 }
 
-fn parse_input(input: &Vec<&str>) -> CubeSet {
-    input
-        .iter()
-        .enumerate()
-        .map(|(y, s)| {
-            s.chars().enumerate().filter_map(move |(x, c)| {
-                (c == '#').then_some((x as isize, y as isize, 0isize, 0isize))
-            })
-        })
-        .flatten()
-        .collect()
+fn parse_input(input: &Vec<&str>, hyper: bool) -> Cubes {
+    // active cubes spread by one in each direction per cycle, keep one more empty layer
+    const PADDING: usize = CYCLES + 1;
+    let dims = [
+        input[0].len() + 2 * PADDING,
+        input.len() + 2 * PADDING,
+        1 + 2 * PADDING,
+        if hyper { 1 + 2 * PADDING } else { 1 },
+    ];
+    let mut cubes = Cubes {
+        dims,
+        origin: [PADDING, PADDING, PADDING, if hyper { PADDING } else { 0 }],
+        active: BitSet::with_capacity(dims.iter().product()),
+    };
+    for (y, s) in input.iter().enumerate() {
+        for (x, c) in s.chars().enumerate() {
+            if c == '#' {
+                let idx = cubes.index((x as isize, y as isize, 0, 0)).unwrap();
+                cubes.active.insert(idx);
+            }
+        }
+    }
+    cubes
 }
 
-fn simulate(cubes: &mut CubeSet, hyper: bool) {
-    let mut activate = CubeVec::new();
-    let mut deactivate = CubeVec::new();
-
-    let mut inactives = HashMap::<Point, usize>::new();
-
-    // check current state
-    for cube in cubes.iter() {
-        let mut neighbors = CubeVec::with_capacity(if hyper { 80 } else { 26 });
-        for x in -1..=1 {
-            for y in -1..=1 {
-                for z in -1..=1 {
-                    for w in if hyper { -1..=1 } else { 0..=0 } {
-                        if x != 0 || y != 0 || z != 0 || w != 0 {
-                            neighbors.push((cube.0 + x, cube.1 + y, cube.2 + z, cube.3 + w));
-                        }
+fn simulate(cubes: &mut Cubes) {
+    // index offsets of all neighbors
+    let [dx, dy, dz, _] = cubes.dims;
+    let strides = [1, dx, dx * dy, dx * dy * dz];
+    let range = |dim: usize| if cubes.dims[dim] > 1 { -1..=1 } else { 0..=0 };
+    let mut offsets = Vec::with_capacity(80);
+    for x in range(0) {
+        for y in range(1) {
+            for z in range(2) {
+                for w in range(3) {
+                    if x != 0 || y != 0 || z != 0 || w != 0 {
+                        offsets.push([x, y, z, w].iter().zip(strides).map(|(d, s)| d * s as isize).sum::<isize>());
                     }
                 }
             }
         }
+    }
 
-        let mut actives = 0usize;
-        for neighbor in neighbors.iter() {
-            if cubes.contains(neighbor) {
-                actives += 1;
-            } else {
-                inactives
-                    .entry(*neighbor)
-                    .and_modify(|n| *n += 1)
-                    .or_insert(1);
-            }
+    // count active neighbors of all cubes
+    let mut counts = vec![0u8; cubes.dims.iter().product()];
+    for cube in cubes.active.iter() {
+        // neighbor offsets wrap around rows at the border of the box
+        debug_assert!(cubes.is_interior(cube), "cube {cube} at the border, more than {CYCLES} cycles?");
+        for &offset in &offsets {
+            counts[cube.wrapping_add_signed(offset)] += 1;
         }
+    }
 
-        // active remains active if 2 or 3 neighbors are active
-        if actives < 2 || actives > 3 {
-            deactivate.push(*cube);
+    // active remains active if 2 or 3 neighbors are active,
+    // inactive becomes active if 3 neighbors are active
+    let mut next = BitSet::with_capacity(counts.len());
+    for (cube, &n) in counts.iter().enumerate() {
+        if n == 3 || (n == 2 && cubes.active.contains(cube)) {
+            next.insert(cube);
         }
     }
+    cubes.active = next;
+}
 
-    // inactive becomes active if 3 neighbors are active
-    for (cube, n) in inactives.iter() {
-        if *n == 3 {
-            activate.push(*cube);
+/// Active cubes in a dense box with room to grow for all cycles
+#[derive(Clone)]
+struct Cubes {
+    dims: [usize; 4],
+    /// position of the input's first cube in the box
+    origin: [usize; 4],
+    active: BitSet,
+}
+
+impl Cubes {
+    fn index(&self, point: Point) -> Option<usize> {
+        let point = [point.0, point.1, point.2, point.3];
+        let mut idx = 0;
+        for dim in (0..4).rev() {
+            let coord = self.origin[dim].checked_add_signed(point[dim]).filter(|&c| c < self.dims[dim])?;
+            idx = idx * self.dims[dim] + coord;
         }
+        Some(idx)
     }
 
-    // transition to next state
-    for cube in &deactivate {
-        cubes.remove(cube);
+    /// whether all neighbors of the cube at the index are in the box
+    fn is_interior(&self, mut idx: usize) -> bool {
+        self.dims.iter().all(|&dim| {
+            let coord = idx % dim;
+            idx /= dim;
+            dim == 1 || (1..dim - 1).contains(&coord)
+        })
     }
-    for cube in activate {
-        cubes.insert(cube);
+
+    #[cfg(test)]
+    fn contains(&self, point: &Point) -> bool {
+        self.index(*point).is_some_and(|idx| self.active.contains(idx))
+    }
+
+    fn len(&self) -> usize {
+        self.active.len()
     }
 }
 
 type Point = (isize, isize, isize, isize);
-type CubeVec = Vec<Point>;
-type CubeSet = HashSet<Point>;
 
 #[test]
 pub fn test() {
     let input = vec![".#.", "..#", "###"];
 
-    let cubes = parse_input(&input);
+    // growing beyond the padding is caught in debug builds
+    if cfg!(debug_assertions) {
+        let mut cubes = parse_input(&input, false);
+        let border = cubes.index((-(cubes.origin[0] as isize), 1, 0, 0)).unwrap();
+        cubes.active.insert(border);
+        assert!(std::panic::catch_unwind(move || simulate(&mut cubes)).is_err());
+    }
+
+    let cubes = parse_input(&input, false);
     assert_eq!(cubes.len(), 5);
     assert!([
         (1, 0, 0, 0),
@@ -113,25 +157,25 @@ pub fn test() {
     {
         let mut cubes_a = cubes.clone();
 
-        simulate(&mut cubes_a, false);
+        simulate(&mut cubes_a);
         assert_eq!(cubes_a.len(), 11);
 
-        simulate(&mut cubes_a, false);
+        simulate(&mut cubes_a);
         assert_eq!(cubes_a.len(), 21);
 
-        simulate(&mut cubes_a, false);
+        simulate(&mut cubes_a);
         assert_eq!(cubes_a.len(), 38);
     }
 
     assert_eq!(a(&input), "112");
 
     {
-        let mut cubes_b = cubes.clone();
+        let mut cubes_b = parse_input(&input, true);
 
-        simulate(&mut cubes_b, true);
+        simulate(&mut cubes_b);
         assert_eq!(cubes_b.len(), 29);
 
-        simulate(&mut cubes_b, true);
+        simulate(&mut cubes_b);
         assert_eq!(cubes_b.len(), 60);
     }
 
@@ -1,90 +1,176 @@
 //! Day 18: Operation Order
 
+use std::{fmt, iter::Peekable, str::Bytes};
+
 /// sum of evaluated expressions with equal precedence
 pub fn a(input: &Vec<&str>) -> String {
-    let expressions = parse_input(input, false);
-    expressions.iter().map(|e| solve(e)).sum::<u64>().to_string()
+    let expressions = parse_input(input, EQUAL_PRECEDENCE);
+    expressions.iter().map(|e| e.evaluate()).sum::<u64>().to_string()
 }
 
 /// sum of evaluated expressions with addition having higher precedence
 pub fn b(input: &Vec<&str>) -> String {
-    let expressions = parse_input(input, true);
-    expressions.iter().map(|e| solve(e)).sum::<u64>().to_string()
+    let expressions = parse_input(input, ADDITION_FIRST);
+    expressions.iter().map(|e| e.evaluate()).sum::<u64>().to_string()
 }
 
-fn parse_input(input: &Vec<&str>, advanced: bool) -> Vec<Expression> {
-    input
-        .iter()
-        .map(|s| {
-            let mut stack = vec![Expression::new()];
-            for b in s.bytes() {
-                match b {
-                    b'0'..=b'9' => stack.last_mut().unwrap().push(Element::Number((b - b'0') as u64)),
-                    b'+' => stack.last_mut().unwrap().push(Element::Operator(Operator::Add)),
-                    b'*' => stack.last_mut().unwrap().push(Element::Operator(Operator::Multiply)),
-                    b'(' => stack.push(Expression::new()),
-                    b')' => {
-                        let expression = stack.pop().unwrap();
-                        stack.last_mut().unwrap().push(Element::Parentheses(expression));
-                    }
-                    _ => {},
-                }
-                if advanced {
-                    if let Some(expression) = stack.last_mut() {
-                        if let Some(Element::Operator(op)) = expression.iter().nth_back(1) {
-                            if *op == Operator::Add {
-                                // precedence for '+', so wrap 'a + b' in parentheses
-                                let exp = expression.split_off(expression.len() - 3);
-                                expression.push(Element::Parentheses(exp));
-                            }
-                        }
-                    }
+/// Operators are evaluated left to right
+const EQUAL_PRECEDENCE: &[Precedence] = &[
+    (Operator::Add, 1, Associativity::Left),
+    (Operator::Multiply, 1, Associativity::Left),
+];
+
+/// Addition binds stronger than multiplication
+const ADDITION_FIRST: &[Precedence] = &[
+    (Operator::Add, 2, Associativity::Left),
+    (Operator::Multiply, 1, Associativity::Left),
+];
+
+fn parse_input(input: &Vec<&str>, precedence: &[Precedence]) -> Vec<Expression> {
+    input.iter().map(|s| Parser::new(s, precedence).parse()).collect()
+}
+
+/// Operator-precedence (Pratt) parser for a given precedence table
+struct Parser<'a> {
+    tokens: Peekable<Tokens<'a>>,
+    precedence: &'a [Precedence],
+}
+
+impl<'a> Parser<'a> {
+    fn new(s: &'a str, precedence: &'a [Precedence]) -> Self {
+        Self {
+            tokens: Tokens(s.bytes().peekable()).peekable(),
+            precedence,
+        }
+    }
+
+    fn parse(mut self) -> Expression {
+        let expression = self.expression(0);
+        assert!(self.tokens.next().is_none(), "unexpected token");
+        expression
+    }
+
+    /// parses operands and operators binding at least with `min_power`
+    fn expression(&mut self, min_power: u8) -> Expression {
+        let mut lhs = match self.tokens.next() {
+            Some(Token::Number(n)) => Expression::Number(n),
+            Some(Token::Open) => {
+                let expression = self.expression(0);
+                assert_eq!(self.tokens.next(), Some(Token::Close), "unbalanced parentheses");
+                expression
+            }
+            _ => unreachable!("expected operand"),
+        };
+
+        while let Some(&Token::Operator(op)) = self.tokens.peek() {
+            let (left_power, right_power) = self.binding_power(op);
+            if left_power < min_power {
+                break;
+            }
+            self.tokens.next();
+            let rhs = self.expression(right_power);
+            lhs = Expression::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        lhs
+    }
+
+    /// binding power of the operator to its left and right operand
+    fn binding_power(&self, op: Operator) -> (u8, u8) {
+        let &(_, level, associativity) = self
+            .precedence
+            .iter()
+            .find(|(operator, _, _)| *operator == op)
+            .expect("operator missing in precedence table");
+        match associativity {
+            Associativity::Left => (2 * level, 2 * level + 1),
+            #[cfg(test)]
+            Associativity::Right => (2 * level + 1, 2 * level),
+        }
+    }
+}
+
+/// Tokenizer of an expression, skipping whitespace
+struct Tokens<'a>(Peekable<Bytes<'a>>);
+
+impl Iterator for Tokens<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        while self.0.next_if_eq(&b' ').is_some() {}
+        let token = match self.0.next()? {
+            b @ b'0'..=b'9' => {
+                let mut n = (b - b'0') as u64;
+                while let Some(b) = self.0.next_if(u8::is_ascii_digit) {
+                    n = n * 10 + (b - b'0') as u64;
                 }
+                Token::Number(n)
             }
-            stack.pop().unwrap()
-        })
-        .collect()
+            b'+' => Token::Operator(Operator::Add),
+            b'*' => Token::Operator(Operator::Multiply),
+            b'(' => Token::Open,
+            b')' => Token::Close,
+            _ => unreachable!("invalid character"),
+        };
+        Some(token)
+    }
 }
 
-fn solve(expression: &Expression) -> u64 {
-    let mut result = 0;
-    let mut operator = Operator::None;
-    for e in expression {
-        match e {
-            Element::Number(n) => match operator {
-                Operator::None => result = *n,
-                Operator::Add => result += n,
-                Operator::Multiply => result *= n,
-            },
-            Element::Operator(op) => operator = *op,
-            Element::Parentheses(exp) => {
-                let n = solve(exp);
-                match operator {
-                    Operator::None => result = n,
-                    Operator::Add => result += n,
-                    Operator::Multiply => result *= n,
-                };
-            },
+impl Expression {
+    fn evaluate(&self) -> u64 {
+        match self {
+            Expression::Number(n) => *n,
+            Expression::Binary(Operator::Add, lhs, rhs) => lhs.evaluate() + rhs.evaluate(),
+            Expression::Binary(Operator::Multiply, lhs, rhs) => lhs.evaluate() * rhs.evaluate(),
         }
     }
+}
 
-    result
+/// Fully parenthesised expression
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::Number(n) => write!(f, "{n}"),
+            Expression::Binary(op, lhs, rhs) => {
+                let op = match op {
+                    Operator::Add => '+',
+                    Operator::Multiply => '*',
+                };
+                write!(f, "({lhs} {op} {rhs})")
+            }
+        }
+    }
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 enum Operator {
-    None,
     Add,
     Multiply,
 }
 
-enum Element {
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Associativity {
+    Left,
+    /// not needed by the puzzle, for experimenting with other tables
+    #[cfg(test)]
+    Right,
+}
+
+/// Operator with its precedence level, higher levels bind stronger
+type Precedence = (Operator, u8, Associativity);
+
+#[derive(Debug, PartialEq)]
+enum Token {
     Number(u64),
     Operator(Operator),
-    Parentheses(Expression),
+    Open,
+    Close,
 }
 
-type Expression = Vec<Element>;
+#[derive(Debug)]
+enum Expression {
+    Number(u64),
+    Binary(Operator, Box<Expression>, Box<Expression>),
+}
 
 #[test]
 pub fn test() {
@@ -99,4 +185,21 @@ pub fn test() {
 
     assert_eq!(a(&input), "26457");
     assert_eq!(b(&input), "694173");
+
+    let parse = |s, precedence| Parser::new(s, precedence).parse().to_string();
+    assert_eq!(parse("1 + 2 * 3 + 4", EQUAL_PRECEDENCE), "(((1 + 2) * 3) + 4)");
+    assert_eq!(parse("1 + 2 * 3 + 4", ADDITION_FIRST), "((1 + 2) * (3 + 4))");
+    let multiplication_first = &[
+        (Operator::Add, 1, Associativity::Right),
+        (Operator::Multiply, 2, Associativity::Left),
+    ];
+    assert_eq!(parse("12 + 2 * 3 * (4 + 5) + 6", multiplication_first), "(12 + (((2 * 3) * (4 + 5)) + 6))");
+    // evaluated right to left
+    let right_to_left = &[
+        (Operator::Add, 1, Associativity::Right),
+        (Operator::Multiply, 1, Associativity::Right),
+    ];
+    assert_eq!(parse("2 * 3 + 4", right_to_left), "(2 * (3 + 4))");
+    assert_eq!(Parser::new("2 * 3 + 4", right_to_left).parse().evaluate(), 14);
+    assert_eq!(Parser::new("2 * 3 + 4", EQUAL_PRECEDENCE).parse().evaluate(), 10);
 }
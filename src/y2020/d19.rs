@@ -1,6 +1,6 @@
 //! Day 19: Monster Messages
 
-use std::{collections::HashMap, vec};
+use std::collections::{HashMap, HashSet};
 
 /// number of messages matching first rule
 pub fn a(input: &Vec<&str>) -> String {
@@ -9,24 +9,10 @@ pub fn a(input: &Vec<&str>) -> String {
 }
 
 /// number of messages matching recursive rules
-/// * first 3 levels of rules are the same, and imply 42{2,} 31+
-/// * message lengths in example are multiples of 5, in full input it's 8
 pub fn b(input: &Vec<&str>) -> String {
     let (mut rules, messages) = parse_input(input);
     replace_rules(&mut rules);
-    let sub_msg_length = rule_length(42, &rules);
-    messages.iter().filter(|msg| {
-        let (mut offset, mut n42, mut n31) = (0, 0, 0);
-        while offset < msg.len() && validate(&msg[offset..offset + sub_msg_length], 42, &rules)  {
-            n42 += 1;
-            offset += sub_msg_length;
-        }
-        while offset < msg.len() && validate(&msg[offset..offset + sub_msg_length], 31, &rules)  {
-            n31 += 1;
-            offset += sub_msg_length;
-        }
-        n42 >= 2 && n31 >= 1 && n42 > n31 && offset == msg.len()
-    }).count().to_string()
+    messages.iter().filter(|msg| validate(msg, 0, &rules)).count().to_string()
 }
 
 fn parse_input<'a>(input: &'a Vec<&'a str>) -> (Rules, Vec<&'a str>) {
@@ -69,38 +55,69 @@ fn parse_input<'a>(input: &'a Vec<&'a str>) -> (Rules, Vec<&'a str>) {
     (rules, messages)
 }
 
+/// Whether the whole message matches the rule
+///
+/// Uses an Earley recognizer, so rules may be recursive in any way.
 fn validate(msg: &str, rule: u8, rules: &Rules) -> bool {
-    fn validate_rule(depth: usize, submsg: &str, rule: u8, rules: &Rules) -> Option<usize> {
-        // println!("{}rule {} - {:?}", ".".repeat(depth), rule, submsg);
-        if submsg.is_empty() {
-            return None;
+    let msg = msg.as_bytes();
+    let alternatives = |rule: u8| match rules.get(&rule) {
+        Some(Rule::Sub(alternatives)) => alternatives,
+        _ => unreachable!("invalid rule number"),
+    };
+
+    // chart of partially matched alternatives ending at each position
+    let mut chart = vec![Vec::new(); msg.len() + 1];
+    let mut seen = vec![HashSet::new(); msg.len() + 1];
+    let mut add = |chart: &mut Vec<Vec<Item>>, pos: usize, item: Item| {
+        if seen[pos].insert(item) {
+            chart[pos].push(item);
+        }
+    };
+    match rules.get(&rule) {
+        Some(Rule::Literal(c)) => return msg == [*c as u8],
+        _ => {
+            for alt in 0..alternatives(rule).len() {
+                add(&mut chart, 0, Item { rule, alt, dot: 0, origin: 0 });
+            }
         }
-        match rules.get(&rule) {
-            Some(Rule::Literal(c)) => submsg.starts_with(*c).then_some(1),
-            Some(Rule::Sub(alternatives)) => {
-                // println!("{}> alt: {:?}", ".".repeat(depth), alternatives);
-                alternatives.iter().find_map(|subrules| {
-                    let mut offset = 0;
-                    for r in subrules {
-                        // println!("{}> trying subrule {}", ".".repeat(depth), r);
-                        if let Some(inc) = validate_rule(depth + 1, &submsg[offset..], *r, rules) {
-                            // println!("{}> subrule {} consumed {}", ".".repeat(depth), r, inc);
-                            offset += inc;
-                        } else {
-                            // println!("{}> subrule {} does not match", ".".repeat(depth), r);
-                            return None;
-                        }
+    }
+
+    for pos in 0..=msg.len() {
+        let mut i = 0;
+        while i < chart[pos].len() {
+            let item = chart[pos][i];
+            i += 1;
+            let Some(&next) = alternatives(item.rule)[item.alt].get(item.dot) else {
+                // complete: advance items waiting for this rule
+                for j in 0..chart[item.origin].len() {
+                    let parent = chart[item.origin][j];
+                    if alternatives(parent.rule)[parent.alt].get(parent.dot) == Some(&item.rule) {
+                        add(&mut chart, pos, Item { dot: parent.dot + 1, ..parent });
+                    }
+                }
+                continue;
+            };
+            match rules.get(&next) {
+                // scan
+                Some(Rule::Literal(c)) => {
+                    if msg.get(pos) == Some(&(*c as u8)) {
+                        add(&mut chart, pos + 1, Item { dot: item.dot + 1, ..item });
                     }
-                    Some(offset)
-                })
-            },
-            None => unreachable!("invalid rule number"),
+                }
+                // predict
+                Some(Rule::Sub(next_alternatives)) => {
+                    for alt in 0..next_alternatives.len() {
+                        add(&mut chart, pos, Item { rule: next, alt, dot: 0, origin: pos });
+                    }
+                }
+                None => unreachable!("invalid rule number"),
+            }
         }
     }
 
-    let result = validate_rule(0, msg, rule, rules);
-    // println!("msg {:?}, len {} - {:?}", msg, msg.len(), result);
-    result == Some(msg.len())
+    chart[msg.len()]
+        .iter()
+        .any(|item| item.rule == rule && item.origin == 0 && item.dot == alternatives(rule)[item.alt].len())
 }
 
 fn replace_rules(rules: &mut Rules) {
@@ -108,27 +125,21 @@ fn replace_rules(rules: &mut Rules) {
     rules.insert(11, Rule::Sub(vec![vec![42, 31], vec![42, 11, 31]]));
 }
 
-fn rule_length(rule: u8, rules: &Rules) -> usize {
-    let mut rule_stack = vec![rule];
-    let mut length = 0;
-    while let Some(r) = rule_stack.pop() {
-        match rules.get(&r) {
-            Some(Rule::Literal(_)) => length += 1,
-            Some(Rule::Sub(alternatives)) => {
-                rule_stack.append(&mut alternatives.first().unwrap().clone());
-            }
-            None => unreachable!("invalid rule number"),
-        }
-    }
-    length
-}
-
 enum Rule {
     Literal(char),
     Sub(Vec<Vec<u8>>),
 }
 type Rules = HashMap<u8, Rule>;
 
+/// Alternative of a rule matched up to `dot`, starting at `origin`
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Item {
+    rule: u8,
+    alt: usize,
+    dot: usize,
+    origin: usize,
+}
+
 #[test]
 pub fn test() {
     let input = vec![
@@ -217,13 +228,17 @@ pub fn test() {
     replace_rules(&mut rules);
     assert_eq!(rules.len(), 31);
     assert_eq!(messages.len(), 15);
-    assert_eq!(rule_length(42, &rules), 5);
-    assert_eq!(rule_length(31, &rules), 5);
-    // assert_eq!(validate(messages[0], &rules), false);
-    // assert_eq!(validate(messages[1], &rules), true);
-    // assert_eq!(validate(messages[2], &rules), true);
-    // assert_eq!(validate(messages[3], &rules), true);
-    // assert_eq!(validate(messages[4], &rules), true);
+    assert!(!validate(messages[0], 0, &rules));
+    assert!(validate(messages[1], 0, &rules));
+    assert!(validate(messages[2], 0, &rules));
+    assert!(validate(messages[3], 0, &rules));
+    assert!(validate(messages[4], 0, &rules));
 
     assert_eq!(b(&input3), "12");
+
+    // left recursion and cycles of rules
+    let input4 = vec![
+        "0: 0 1 | 2", "1: \"a\"", "2: 3 | 1 1", "3: 2 | 4", "4: \"b\"", "", "b", "baa", "aa", "aaa", "ab",
+    ];
+    assert_eq!(a(&input4), "4");
 }
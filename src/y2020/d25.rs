@@ -1,19 +1,28 @@
-//! Day 25
+//! Day 25: Combo Breaker
 
-/// part a
-pub fn a(_input: &Vec<&str>) -> String {
-    String::new()
+use crate::utils::num;
+
+const SUBJECT: u64 = 7;
+const MODULUS: u64 = 20201227;
+
+/// encryption key of card and door handshake
+pub fn a(input: &Vec<&str>) -> String {
+    let (card_key, door_key) = parse_input(input);
+    let card_loop_size = num::discrete_log(SUBJECT, card_key, MODULUS).expect("invalid public key");
+    num::mod_pow(door_key, card_loop_size, MODULUS).to_string()
 }
 
-/// part b
-pub fn b(_input: &Vec<&str>) -> String {
-    String::new()
+fn parse_input(input: &[&str]) -> (u64, u64) {
+    (input[0].parse().unwrap(), input[1].parse().unwrap())
 }
 
 #[test]
 pub fn test() {
-    let input = vec![];
+    let input = vec!["5764801", "17807724"];
+
+    assert_eq!(num::discrete_log(SUBJECT, 5764801, MODULUS), Some(8));
+    assert_eq!(num::discrete_log(SUBJECT, 17807724, MODULUS), Some(11));
+    assert_eq!(num::mod_pow(5764801, 11, MODULUS), 14897079);
 
-    assert_eq!(a(&input), "");
-    assert_eq!(b(&input), "");
+    assert_eq!(a(&input), "14897079");
 }
@@ -22,7 +22,7 @@ mod d21;
 // mod d22;
 // mod d23;
 // mod d24;
-// mod d25;
+mod d25;
 
 use crate::{args::PartValues::{self, *}, SolveFunc};
 
@@ -76,7 +76,15 @@ pub fn solver(day: u8, part: PartValues) -> SolveFunc {
         // (23, B) => d23::b,
         // (24, A) => d24::a,
         // (24, B) => d24::b,
-        // (25, A) => d25::a,
+        (25, A) => d25::a,
         _ => unimplemented!("Unsupported puzzle: {day} {part:?}"),
     }
 }
+
+/// Disassembler of the puzzle input program, for days with a machine
+pub fn disassembler(day: u8) -> Option<SolveFunc> {
+    match day {
+        8 => Some(d08::disassemble),
+        _ => None,
+    }
+}
@@ -1,5 +1,6 @@
 //! Day 4: Giant Squid
 
+use crate::trace;
 use std::collections::BTreeSet;
 
 type Board = Vec<u8>;
@@ -31,7 +32,7 @@ pub fn b(input: &Vec<&str>) -> String {
     let count = boards.len();
     let mut winners = BTreeSet::new();
     for number in numbers {
-        println!("Calling {number}");
+        trace::trace!("Calling {number}");
         for (i, board) in boards.iter_mut().enumerate() {
             if winners.contains(&i) {
                 continue;
@@ -44,7 +45,7 @@ pub fn b(input: &Vec<&str>) -> String {
                     .map(|&x| u32::from(x))
                     .sum::<u32>();
                 let score = u32::from(number) * sum;
-                println!("> Board {i} scored {score} ({}/{})", winners.len(), count);
+                trace::debug!("> Board {i} scored {score} ({}/{})", winners.len(), count);
                 if winners.len() == count {
                     // Note: this will not give the right solution as the two
                     //       last boards are called at the same time
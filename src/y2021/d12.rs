@@ -1,5 +1,6 @@
 //! Day 12: Passage Pathing
 
+use crate::trace;
 use std::collections::BTreeMap;
 
 type Graph<V> = BTreeMap<V, Vec<V>>;
@@ -73,8 +74,7 @@ fn count_paths(graph: &Graph<&str>, allow_single_duplicate: bool) -> usize {
     ) {
         for &node in nodes {
             if node == "end" {
-                #[cfg(test)]
-                println!("{},end", path.join(","));
+                trace::trace!("{},end", path.join(","));
 
                 *paths += 1;
                 continue;
@@ -90,8 +90,7 @@ fn count_paths(graph: &Graph<&str>, allow_single_duplicate: bool) -> usize {
         }
     }
 
-    #[cfg(test)]
-    println!("{:?}", graph);
+    trace::debug!("{:?}", graph);
 
     let mut paths = 0;
     let mut path = Vec::new();
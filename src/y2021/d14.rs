@@ -1,5 +1,6 @@
 //! Day 14: Extended Polymerization
 
+use crate::trace;
 use std::collections::BTreeMap;
 
 /// subtract least common from most common after 10 steps
@@ -70,8 +71,7 @@ fn apply_rules(
         }
     }
 
-    #[cfg(test)]
-    println!("{:?}", pairs);
+    trace::debug!("{:?}", pairs);
 
     // count elements in pairs
     let mut elements: BTreeMap<char, usize> = BTreeMap::new();
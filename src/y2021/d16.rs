@@ -1,10 +1,17 @@
 //! Day 16: Packet Decoder
 
+use std::fmt;
+
+use crate::trace;
+
 struct Packet {
     pub version: u8,
     pub type_id: u8,
     pub value: i64,
     pub size: usize,
+    /// operator payload given as bit length (0) or packet count (1), kept for the encoder
+    #[cfg(test)]
+    pub length_type: u8,
     pub packets: Vec<Packet>,
 }
 
@@ -51,7 +58,48 @@ impl Packet {
             size = i;
         }
         
-        Packet{ version, type_id, value, size, packets }
+        Packet {
+            version,
+            type_id,
+            value,
+            size,
+            #[cfg(test)]
+            length_type: u8::from(type_id != 4 && s[6..=6].eq("1")),
+            packets,
+        }
+    }
+
+    /// Hexadecimal transmission of the packet, padded with zeros to full bytes
+    #[cfg(test)]
+    fn to_hex(&self) -> String {
+        bin_to_hex(&self.to_binary())
+    }
+
+    /// Binary representation, inverse of `from_binary`
+    #[cfg(test)]
+    fn to_binary(&self) -> String {
+        let mut bits = format!("{:03b}{:03b}", self.version, self.type_id);
+        if self.type_id == 4 {
+            // groups of 4 bits, each prefixed by 1 except the last one,
+            // keeping leading zero groups of decoded packets
+            let needed = (64 - self.value.leading_zeros() as usize).div_ceil(4).max(1);
+            let count = needed.max(self.size.saturating_sub(6) / 5);
+            for i in (0..count).rev() {
+                let group = (self.value >> (4 * i)) & 0xf;
+                bits.push_str(&format!("{}{group:04b}", if i == 0 { 0 } else { 1 }));
+            }
+        } else {
+            let payload = self.packets.iter().map(|p| p.to_binary()).collect::<String>();
+            // packet count is limited to 11 bits
+            if self.length_type == 0 || self.packets.len() >= 1 << 11 {
+                assert!(payload.len() < 1 << 15, "payload too long");
+                bits.push_str(&format!("0{:015b}", payload.len()));
+            } else {
+                bits.push_str(&format!("1{:011b}", self.packets.len()));
+            }
+            bits.push_str(&payload);
+        }
+        bits
     }
 
     fn version_sum(&self) -> usize {
@@ -82,6 +130,31 @@ impl Packet {
     }
 }
 
+/// Expression like `sum(prod(3, 4), max(5, 6))`
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.type_id {
+            0 => "sum",
+            1 => "prod",
+            2 => "min",
+            3 => "max",
+            4 => return write!(f, "{}", self.value),
+            5 => "gt",
+            6 => "lt",
+            7 => "eq",
+            8..=u8::MAX => unreachable!(),
+        };
+        write!(f, "{name}(")?;
+        for (i, packet) in self.packets.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{packet}")?;
+        }
+        write!(f, ")")
+    }
+}
+
 /// sum of version numbers in all packets
 pub fn a(input: &Vec<&str>) -> String {
     let packet = parse_input(input);
@@ -91,6 +164,7 @@ pub fn a(input: &Vec<&str>) -> String {
 /// evaluate expression
 pub fn b(input: &Vec<&str>) -> String {
     let packet = parse_input(input);
+    trace::debug!("{packet}");
     packet.evaluate().to_string()
 }
 
@@ -109,6 +183,16 @@ fn hex_to_bin(s: &str) -> String {
         .join("")
 }
 
+#[cfg(test)]
+fn bin_to_hex(s: &str) -> String {
+    // padded with zeros to full bytes
+    let padded = format!("{s:0<width$}", width = s.len().div_ceil(8) * 8);
+    (0..padded.len())
+        .step_by(8)
+        .map(|i| format!("{:02X}", u8::from_str_radix(&padded[i..i + 8], 2).unwrap()))
+        .collect()
+}
+
 #[test]
 pub fn test() {
     let input = vec!["8A004A801A8002F478"];
@@ -168,4 +252,53 @@ pub fn test() {
     assert_eq!(b(&vec!["F600BC2D8F"]), "0");
     assert_eq!(b(&vec!["9C005AC2F8F0"]), "0");
     assert_eq!(b(&vec!["9C0141080250320F1802104A08"]), "1");
+
+    // examples are reproduced by the encoder
+    assert_eq!(Packet::from_binary("1101001000000001").to_hex(), "D201"); // leading zero group
+    for hex in [input[0], input2[0], input3[0], input4[0], "D2FE28", "38006F45291200", "EE00D40C823060"] {
+        assert_eq!(parse_input(&vec![hex]).to_hex(), hex);
+    }
+    // inputs may be padded with more zeros than needed
+    for hex in ["C200B40A82", "04005AC33890", "880086C3E88112", "CE00C43D881120", "9C0141080250320F1802104A08"] {
+        assert!(hex.starts_with(&parse_input(&vec![hex]).to_hex()));
+    }
+    let packet = parse_input(&vec!["9C0141080250320F1802104A08"]);
+    assert_eq!(packet.to_string(), "eq(sum(1, 3), prod(2, 2))");
+
+    // random packet trees survive encoding and decoding
+    fn random_packet(rng: &mut crate::utils::Rng, depth: usize) -> Packet {
+        let mut packet = Packet {
+            version: (rng.next_u64() % 8) as u8,
+            type_id: if depth == 0 { 4 } else { (rng.next_u64() % 8) as u8 },
+            value: 0,
+            size: 0,
+            length_type: (rng.next_u64() % 2) as u8,
+            packets: Vec::new(),
+        };
+        match packet.type_id {
+            // large literals, limited to avoid overflows in products
+            4 => packet.value = (rng.next_u64() % (1 << (rng.next_u64() % 40))) as i64,
+            1 => {
+                for _ in 0..=rng.next_u64() % 3 {
+                    let mut factor = random_packet(rng, 0);
+                    factor.value %= 1 << 10;
+                    packet.packets.push(factor);
+                }
+            }
+            5..=7 => packet.packets = vec![random_packet(rng, depth - 1), random_packet(rng, depth - 1)],
+            _ => packet.packets = (0..=rng.next_u64() % 3).map(|_| random_packet(rng, depth - 1)).collect(),
+        }
+        packet
+    }
+    let mut rng = crate::utils::Rng::new(16);
+    for _ in 0..200 {
+        let depth = (rng.next_u64() % 5) as usize;
+        let packet = random_packet(&mut rng, depth);
+        let hex = packet.to_hex();
+        let decoded = parse_input(&vec![&hex]);
+        assert_eq!(decoded.to_string(), packet.to_string());
+        assert_eq!(decoded.version_sum(), packet.version_sum());
+        assert_eq!(decoded.evaluate(), packet.evaluate());
+        assert_eq!(decoded.to_binary(), packet.to_binary());
+    }
 }
@@ -1,5 +1,7 @@
 //! Day 17: Trick Shot
 
+use crate::trace;
+
 type Point = (i32, i32);
 
 #[derive(Debug, PartialEq)]
@@ -31,7 +33,7 @@ pub fn a(input: &Vec<&str>) -> String {
             if evaluate(&(x, y), &target, &mut max_y) && max_y > total_max_y {
                 total_max_y = max_y;
                 min_v_y = y;
-                println!("{} {} {}", x, y, max_y);
+                trace::debug!("{} {} {}", x, y, max_y);
             }
         }
     }
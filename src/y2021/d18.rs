@@ -1,141 +1,124 @@
 //! Day 18: Snailfish
 
-use std::{str::FromStr, ops::Add, fmt::Display};
+use crate::utils::parallel::par_map;
+use std::{fmt::Display, ops::Add, str::FromStr};
 
+/// Snailfish number as its regular numbers from left to right, each with its
+/// nesting depth, e.g. `[[1,2],3]` is `(2, 1), (2, 2), (1, 3)`
 #[derive(Clone, Debug, PartialEq)]
-enum SnailfishNumber {
-    Pair(Box<SnailfishNumber>, Box<SnailfishNumber>),
-    Value(usize),
+struct SnailfishNumber {
+    leaves: Vec<Leaf>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Leaf {
+    depth: u8,
+    value: usize,
 }
 
 impl SnailfishNumber {
-    /// Recursively calculates the number's magnitude.
+    /// Calculates the number's magnitude by folding pairs of equal depth.
     pub fn magnitude(&self) -> usize {
-        match self {
-            Self::Pair(a, b) => 3 * a.magnitude() + 2 * b.magnitude(),
-            Self::Value(v) => *v,
+        let mut stack: Vec<Leaf> = Vec::with_capacity(self.leaves.len());
+        for &leaf in &self.leaves {
+            let mut leaf = leaf;
+            while let Some(&left) = stack.last() {
+                if left.depth != leaf.depth {
+                    break;
+                }
+                stack.pop();
+                leaf = Leaf {
+                    depth: leaf.depth - 1,
+                    value: 3 * left.value + 2 * leaf.value,
+                };
+            }
+            stack.push(leaf);
         }
+        stack[0].value
     }
 
     /// Applies 'explode' operation and return whether it happened.
-    /// 
+    ///
     /// If any pair is nested inside four pairs, the leftmost such pair explodes.
     fn try_explode(&mut self) -> bool {
-        /// Recurses to leftmost leaf and adds value.
-        fn add_to_leftmost(
-            number: &mut SnailfishNumber,
-            lval: &usize,
-        ) {
-            match number {
-                SnailfishNumber::Pair(lnum, _) => add_to_leftmost(lnum, lval),
-                SnailfishNumber::Value(v) => *v += *lval,
-            }
+        // numbers are reduced after each addition, so such pairs consist of two regular numbers
+        let Some(i) = self.leaves.iter().position(|leaf| leaf.depth > 4) else {
+            return false;
+        };
+        let (left, right) = (self.leaves[i].value, self.leaves[i + 1].value);
+        if i > 0 {
+            self.leaves[i - 1].value += left;
         }
-
-        /// Recurses to rightmost leaf and adds value.
-        fn add_to_rightmost(
-            number: &mut SnailfishNumber,
-            rval: &usize,
-        ) {
-            match number {
-                SnailfishNumber::Pair(_, rnum) => add_to_rightmost(rnum, rval),
-                SnailfishNumber::Value(v) => *v += *rval,
-            }
-        }
-
-        /// Internal explode - tracks depth and bubbles up values to add.
-        fn explode(
-            number: &mut SnailfishNumber,
-            depth: usize,
-            lval: &mut usize,
-            rval: &mut usize
-        ) -> bool {
-            if let SnailfishNumber::Pair(lnum, rnum) = number {
-                if depth == 4 {
-                    if let SnailfishNumber::Value(v) = **lnum {
-                        *lval = v;
-                    }
-                    if let SnailfishNumber::Value(v) = **rnum {
-                        *rval = v;
-                    }
-                    *number = SnailfishNumber::Value(0);
-                    true
-                } else if explode(lnum, depth + 1, lval, rval) {
-                    if *rval != 0 {
-                        add_to_leftmost(rnum, rval);
-                        *rval = 0;
-                    }
-                    true
-                } else if explode(rnum, depth + 1, lval, rval) {
-                    if *lval != 0 {
-                        add_to_rightmost(lnum, lval);
-                        *lval = 0;
-                    }
-                    true
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
+        if let Some(next) = self.leaves.get_mut(i + 2) {
+            next.value += right;
         }
-
-        let mut lval = 0;
-        let mut rval = 0;
-        explode(self, 0, &mut lval, &mut rval)
+        self.leaves[i] = Leaf {
+            depth: self.leaves[i].depth - 1,
+            value: 0,
+        };
+        self.leaves.remove(i + 1);
+        true
     }
 
     /// Apply 'split' operation and return whether it happened.
-    /// 
+    ///
     /// If any regular number is 10 or greater, the leftmost such regular number splits.
     fn try_split(&mut self) -> bool {
-        match self {
-            Self::Pair(lnum, rnum) => lnum.try_split() || rnum.try_split(),
-            Self::Value(v) if *v >= 10 => {
-                //let lval = v.div_floor(2);
-                //let rval = v.div_ceil(2);
-                let half = *v / 2;
-                *self = Self::from((half, *v - half));
-                true
+        let Some(i) = self.leaves.iter().position(|leaf| leaf.value >= 10) else {
+            return false;
+        };
+        let Leaf { depth, value } = self.leaves[i];
+        let half = value / 2;
+        self.leaves[i] = Leaf {
+            depth: depth + 1,
+            value: half,
+        };
+        self.leaves.insert(
+            i + 1,
+            Leaf {
+                depth: depth + 1,
+                value: value - half,
             },
-            _ => false,
-        }
+        );
+        true
     }
-}
 
-impl From<(usize, usize)> for SnailfishNumber {
-    fn from(pair: (usize, usize)) -> Self {
-        Self::Pair(
-            Box::new(Self::Value(pair.0)),
-            Box::new(Self::Value(pair.1)),
-        )
+    fn reduce(&mut self) {
+        while self.try_explode() || self.try_split() {}
     }
-}
 
-impl From<(SnailfishNumber, usize)> for SnailfishNumber {
-    fn from(pair: (SnailfishNumber, usize)) -> Self {
-        Self::Pair(
-            Box::new(pair.0),
-            Box::new(Self::Value(pair.1)),
-        )
+    /// Writes the element at `*i` with given depth, advancing `*i` past it
+    fn fmt_element(&self, f: &mut std::fmt::Formatter<'_>, i: &mut usize, depth: u8) -> std::fmt::Result {
+        let leaf = self.leaves[*i];
+        if leaf.depth == depth {
+            *i += 1;
+            write!(f, "{}", leaf.value)
+        } else {
+            write!(f, "[")?;
+            self.fmt_element(f, i, depth + 1)?;
+            write!(f, ",")?;
+            self.fmt_element(f, i, depth + 1)?;
+            write!(f, "]")
+        }
     }
 }
 
-impl From<(usize, SnailfishNumber)> for SnailfishNumber {
-    fn from(pair: (usize, SnailfishNumber)) -> Self {
-        Self::Pair(
-            Box::new(Self::Value(pair.0)),
-            Box::new(pair.1),
-        )
+impl From<usize> for SnailfishNumber {
+    fn from(value: usize) -> Self {
+        Self {
+            leaves: vec![Leaf { depth: 0, value }],
+        }
     }
 }
 
-impl From<(SnailfishNumber, SnailfishNumber)> for SnailfishNumber {
-    fn from(pair: (SnailfishNumber, SnailfishNumber)) -> Self {
-        Self::Pair(
-            Box::new(pair.0),
-            Box::new(pair.1),
-        )
+/// Pair of two numbers, without reduction
+impl<A: Into<SnailfishNumber>, B: Into<SnailfishNumber>> From<(A, B)> for SnailfishNumber {
+    fn from(pair: (A, B)) -> Self {
+        let (a, b) = (pair.0.into(), pair.1.into());
+        let leaves = a.leaves.iter().chain(&b.leaves);
+        Self {
+            leaves: leaves.map(|leaf| Leaf { depth: leaf.depth + 1, ..*leaf }).collect(),
+        }
     }
 }
 
@@ -143,47 +126,56 @@ impl FromStr for SnailfishNumber {
     type Err = &'static str;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s[0..=0].eq("[") {
-            let mut open = 0;
-            let mut mid = 0;
-            for (i, c) in s.char_indices() {
-                match c {
-                    '[' => open += 1,
-                    ']' => open -= 1,
-                    ',' if open <= 1 => {
-                        mid = i;
-                        break;
-                    },
-                    _ => (),
+        let mut leaves = Vec::new();
+        let mut depth = 0u8;
+        let mut chars = s.bytes().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                b'[' => depth += 1,
+                b']' => depth = depth.checked_sub(1).ok_or("unbalanced brackets")?,
+                b',' => (),
+                b'0'..=b'9' => {
+                    let mut value = (c - b'0') as usize;
+                    while let Some(c) = chars.next_if(u8::is_ascii_digit) {
+                        value = value * 10 + (c - b'0') as usize;
+                    }
+                    leaves.push(Leaf { depth, value });
                 }
+                _ => return Err("invalid character"),
             }
-            Ok(Self::Pair(
-                Box::new(Self::from_str(&s[1..mid])?),
-                Box::new(Self::from_str(&s[mid+1..s.len()-1])?),
-            ))
-        } else {
-            Ok(Self::Value(s.parse().unwrap()))
         }
+        if depth != 0 || leaves.is_empty() {
+            return Err("incomplete number");
+        }
+        Ok(Self { leaves })
     }
 }
 
 impl Display for SnailfishNumber {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Pair(a, b) => write!(f, "[{},{}]", a, b),
-            Self::Value(v) => write!(f, "{}", v),
-        }
+        self.fmt_element(f, &mut 0, 0)
     }
 }
 
 impl Add for SnailfishNumber {
     type Output = Self;
 
-    fn add(self, rhs: Self) -> Self::Output {
-        let mut result = Self::Pair(Box::new(self), Box::new(rhs));
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self.leaves.extend(rhs.leaves);
+        self.leaves.iter_mut().for_each(|leaf| leaf.depth += 1);
+        self.reduce();
+        self
+    }
+}
 
-        while result.try_explode() || result.try_split() {}
+impl Add for &SnailfishNumber {
+    type Output = SnailfishNumber;
 
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut leaves = Vec::with_capacity(self.leaves.len() + rhs.leaves.len() + 8);
+        leaves.extend(self.leaves.iter().chain(&rhs.leaves).map(|leaf| Leaf { depth: leaf.depth + 1, ..*leaf }));
+        let mut result = SnailfishNumber { leaves };
+        result.reduce();
         result
     }
 }
@@ -205,17 +197,18 @@ pub fn b(input: &Vec<&str>) -> String {
         .iter()
         .map(|&s| s.parse::<SnailfishNumber>().unwrap())
         .collect::<Vec<_>>();
-    let mut max_magnitude = 0;
-    for n1 in &numbers {
-        for n2 in &numbers {
-            if n1 != n2 {
-                let m = (n1.to_owned() + n2.to_owned()).magnitude();
-                if m > max_magnitude {
-                    max_magnitude = m;
-                }
-            }
-        }
-    }
+    // first summands are distributed over threads
+    let indices = (0..numbers.len()).collect::<Vec<_>>();
+    let max_magnitude = par_map(&indices, |&i| {
+        (0..numbers.len())
+            .filter(|&j| j != i)
+            .map(|j| (&numbers[i] + &numbers[j]).magnitude())
+            .max()
+            .unwrap_or(0)
+    })
+    .into_iter()
+    .max()
+    .unwrap();
     max_magnitude.to_string()
 }
 
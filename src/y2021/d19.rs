@@ -1,5 +1,6 @@
 //! Day 19: Beacon Scanner
 
+use crate::trace;
 use std::collections::{BTreeMap, VecDeque};
 
 type Point = (i32, i32, i32);
@@ -233,23 +234,15 @@ fn find_transformations(point_clouds: &PointClouds) -> Transformations {
                     .or_insert(BTreeMap::new())
                     .insert(i, find_transformation(&points_j, &points_i));
 
-                #[cfg(test)]
-                println!("groups {i} and {j} - {} pairs", candidates.len());
+                trace::debug!("groups {i} and {j} - {} pairs", candidates.len());
             }
         }
     }
 
-    #[cfg(test)]
-    {
-        println!("Transformation graph before trimming:");
-        transformations
-            .iter()
-            .for_each(|(k, v)| println!(
-                "  {} -> {:?}",
-                k,
-                v.keys().collect::<Vec<_>>(),
-            ));
-    }
+    trace::trace!(
+        "Transformation graph before trimming:\n{}",
+        format_transformation_graph(&transformations)
+    );
 
     let mut visited = Vec::new();
     let mut pending = VecDeque::from([0usize]);
@@ -274,21 +267,22 @@ fn find_transformations(point_clouds: &PointClouds) -> Transformations {
             });
     }
 
-    #[cfg(test)]
-    {
-        println!("Transformation graph after trimming:");
-        transformations
-            .iter()
-            .for_each(|(k, v)| println!(
-                "  {} -> {:?}",
-                k,
-                v.keys().collect::<Vec<_>>(),
-            ));
-    }
+    trace::trace!(
+        "Transformation graph after trimming:\n{}",
+        format_transformation_graph(&transformations)
+    );
 
     transformations
 }
 
+fn format_transformation_graph(transformations: &Transformations) -> String {
+    transformations
+        .iter()
+        .map(|(k, v)| format!("  {} -> {:?}", k, v.keys().collect::<Vec<_>>()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 fn apply_transformations(
     points: &Points,
     transformations: &Transformations,
@@ -1,6 +1,6 @@
 //! Day 21: Dirac Dice
 
-use std::collections::HashMap;
+use crate::utils::memo::Memo;
 
 type Pair = (usize, usize);
 
@@ -47,46 +47,32 @@ pub fn b(input: &Vec<&str>) -> String {
         (9, 1),  // 1 roll of sum 9
     ];
 
-    /// caches (player, pos, scores) -> combinations
-    type CombCache = HashMap<(bool, Pair, Pair), Pair>;
-
-    /// determines winning combinations based on given position
-    fn simulate(
-        cache: &mut CombCache,
-        pos: &Pair,
-        scores: &Pair,
-        is_player_1: bool,
-    ) -> Pair {
-        let cache_key = (is_player_1, *pos, *scores);
-        if let Some(hit) = cache.get(&cache_key) {
-            return *hit;
-        }
-
-        let mut combs = (0, 0);
-        for (val, num) in ROLLS {
-            let mut new_pos = *pos;
-            let mut new_scores = *scores;
-            if is_player_1 {
-                new_pos.0 = (pos.0 - 1 + val) % 10 + 1;
-                new_scores.0 += new_pos.0;
-            } else {
-                new_pos.1 = (pos.1 - 1 + val) % 10 + 1;
-                new_scores.1 += new_pos.1;
-            }
-            if new_scores.0 < MAX_SCORE && new_scores.1 < MAX_SCORE {
-                let res = simulate(cache, &new_pos, &new_scores, !is_player_1);
-                combs.0 += num * res.0;
-                combs.1 += num * res.1;
-            } else {
-                *(if is_player_1 { &mut combs.0 } else { &mut combs.1 }) += num;
+    // determines winning combinations of (player, pos, scores)
+    let combinations = Memo::<_, Pair>::new().get(
+        (true, pos, (0, 0)),
+        &|simulate, (is_player_1, pos, scores): (bool, Pair, Pair)| {
+            let mut combs = (0, 0);
+            for (val, num) in ROLLS {
+                let mut new_pos = pos;
+                let mut new_scores = scores;
+                if is_player_1 {
+                    new_pos.0 = (pos.0 - 1 + val) % 10 + 1;
+                    new_scores.0 += new_pos.0;
+                } else {
+                    new_pos.1 = (pos.1 - 1 + val) % 10 + 1;
+                    new_scores.1 += new_pos.1;
+                }
+                if new_scores.0 < MAX_SCORE && new_scores.1 < MAX_SCORE {
+                    let res = simulate((!is_player_1, new_pos, new_scores));
+                    combs.0 += num * res.0;
+                    combs.1 += num * res.1;
+                } else {
+                    *(if is_player_1 { &mut combs.0 } else { &mut combs.1 }) += num;
+                }
             }
-        }
-
-        cache.insert(cache_key, combs);
-        combs
-    }
-
-    let combinations = simulate(&mut CombCache::new(), &pos, &(0, 0), true);
+            combs
+        },
+    );
     std::cmp::max(combinations.0, combinations.1).to_string()
 }
 
@@ -1,27 +1,27 @@
 //! Day 22: Reactor Reboot
 
-use std::cmp::{max, min};
+use crate::utils::cuboid::{self, Cuboid};
 
 /// number of active cubes within -50..50 limits
 pub fn a(input: &Vec<&str>) -> String {
     let steps = parse_input(input, true);
-    count_cubes(&steps).to_string()
+    cuboid::count_on(&steps).to_string()
 }
 
 /// number of active cubes in total
 pub fn b(input: &Vec<&str>) -> String {
     let steps = parse_input(input, false);
-    count_cubes(&steps).to_string()
+    cuboid::count_on(&steps).to_string()
 }
 
-fn parse_input(input: &Vec<&str>, limited: bool) -> Vec<Cuboid> {
+fn parse_input(input: &Vec<&str>, limited: bool) -> Vec<Step> {
     let mut steps = Vec::new();
     for s in input {
         let (state, s) = s.split_once(" ").unwrap();
         let ranges = s
             .split(",")
             .map(|r| r[2..].split_once("..").unwrap())
-            .map(|(min, max)| (min.parse::<i32>().unwrap(), max.parse::<i32>().unwrap()))
+            .map(|(min, max)| (min.parse::<i64>().unwrap(), max.parse::<i64>().unwrap()))
             .filter(|(min, max)| !limited || (min.abs() <= 50 && max.abs() <= 50))
             .collect::<Vec<_>>();
 
@@ -29,104 +29,17 @@ fn parse_input(input: &Vec<&str>, limited: bool) -> Vec<Cuboid> {
             continue;
         }
 
-        steps.push(Cuboid {
-            on: state == "on",
-            min: Point {
-                x: ranges[0].0,
-                y: ranges[1].0,
-                z: ranges[2].0,
-            },
-            max: Point {
-                x: ranges[0].1,
-                y: ranges[1].1,
-                z: ranges[2].1,
-            },
-        });
-    }
-    steps
-}
-
-fn count_cubes(steps: &Vec<Cuboid>) -> usize {
-    let mut volume = 0;
-    let mut explicit_steps = Vec::<Cuboid>::new();
-
-    for step in steps {
-        let n = explicit_steps.len();
-        for i in 0..n {
-            let intersection = explicit_steps[i].intersection(&step);
-            if !intersection.is_valid() {
-                continue;
-            }
-            if intersection.on {
-                volume += intersection.volume();
-            } else {
-                volume -= intersection.volume();
-            }
-            explicit_steps.push(intersection);
-        }
-
-        if step.on {
-            volume += step.volume();
-            explicit_steps.push(*step);
-        }
-
-        #[cfg(test)]
-        println!(
-            "step: {:?}, volume: {}, total: {}",
-            step,
-            step.volume(),
-            volume
+        let cuboid = Cuboid::inclusive(
+            [ranges[0].0, ranges[1].0, ranges[2].0],
+            [ranges[0].1, ranges[1].1, ranges[2].1],
         );
+        steps.push((cuboid, state == "on"));
     }
-
-    volume
-}
-
-#[derive(Clone, Copy, Debug)]
-struct Point {
-    x: i32,
-    y: i32,
-    z: i32,
-}
-
-#[derive(Clone, Copy, Debug)]
-struct Cuboid {
-    on: bool,
-    min: Point,
-    max: Point,
+    steps
 }
 
-impl Cuboid {
-    fn volume(&self) -> usize {
-        if self.is_valid() {
-            (self.max.x - self.min.x + 1) as usize
-                * (self.max.y - self.min.y + 1) as usize
-                * (self.max.z - self.min.z + 1) as usize
-        } else {
-            0
-        }
-    }
-
-    fn intersection(&self, other: &Self) -> Self {
-        Self {
-            on: !self.on,
-            min: Point {
-                x: max(self.min.x, other.min.x),
-                y: max(self.min.y, other.min.y),
-                z: max(self.min.z, other.min.z),
-            },
-            max: Point {
-                x: min(self.max.x, other.max.x),
-                y: min(self.max.y, other.max.y),
-                z: min(self.max.z, other.max.z),
-            },
-        }
-    }
-
-    fn is_valid(&self) -> bool {
-        self.min.x <= self.max.x && self.min.y <= self.max.y && self.min.z <= self.max.z
-    }
-}
+/// Cuboid switched on or off
+type Step = (Cuboid<3>, bool);
 
 #[test]
 pub fn test() {
@@ -227,11 +140,11 @@ pub fn test() {
 
     let steps = parse_input(&input1, false);
     assert_eq!(steps.len(), 4);
-    assert_eq!(steps[0].volume(), 27);
-    assert_eq!(steps[1].volume(), 27);
-    assert_eq!(steps[0].intersection(&steps[1]).volume(), 8);
-    assert_eq!(steps[3].volume(), 1);
-    assert_eq!(steps[1].intersection(&steps[3]).volume(), 0);
+    assert_eq!(steps[0].0.volume(), 27);
+    assert_eq!(steps[1].0.volume(), 27);
+    assert_eq!(steps[0].0.intersection(&steps[1].0).volume(), 8);
+    assert_eq!(steps[3].0.volume(), 1);
+    assert_eq!(steps[1].0.intersection(&steps[3].0).volume(), 0);
 
     assert_eq!(a(&input1), "39");
     assert_eq!(a(&input2), "590784");
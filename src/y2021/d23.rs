@@ -1,5 +1,6 @@
 //! Day 23: Amphipod
 
+use crate::trace;
 use std::collections::{BinaryHeap, HashMap};
 
 /// least total energy to move to target positions
@@ -218,7 +219,7 @@ fn least_total_energy(initial_state: &State) -> usize {
 
         if is_target_state(&node.vertex) {
             // println!("{:?} - {} *", node.vertex, node.cost);
-            println!("Searched {search_count} state transitions.");
+            trace::debug!("Searched {search_count} state transitions.");
             return cost;
         }
 
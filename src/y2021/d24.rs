@@ -16,26 +16,217 @@
 //!   ```
 //! * only blocks with `a == 26` can decrease `z` to 0, `z` has to be below 26
 //! * there is an equal number of both types of blocks, they have to be paired
+//!
+//! The solver does not rely on this layout: it searches digits depth-first and
+//! prunes states for which interval analysis of the remaining program shows
+//! that `z` cannot end up as 0.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+};
 
-const DIGIT_COUNT: usize = 14;
+use crate::{
+    trace,
+    vm::{self, Decode, Flow, Machine},
+};
 
 /// largest 14 digit number accepted by given program
 pub fn a(input: &Vec<&str>) -> String {
     let program = parse_input(input);
-    let number = find_accepted_number(&program, true);
+    let number = find_accepted_number(&program, true).expect("no number accepted");
     number.iter().map(|n| n.to_string()).collect::<String>()
 }
 
 /// smallest 14 digit number accepted by given program
 pub fn b(input: &Vec<&str>) -> String {
     let program = parse_input(input);
-    let number = find_accepted_number(&program, false);
+    let number = find_accepted_number(&program, false).expect("no number accepted");
     number.iter().map(|n| n.to_string()).collect::<String>()
 }
 
+/// program as annotated pseudo-code
+pub fn disassemble(input: &Vec<&str>) -> String {
+    vm::disassemble(&parse_input(input))
+}
+
 fn parse_input(input: &Vec<&str>) -> Program {
-    let mut program = Program::with_capacity(input.len());
-    for &line in input {
+    input.iter().map(|&line| Instruction::decode(line)).collect()
+}
+
+fn find_accepted_number(program: &[Instruction], maximize: bool) -> Option<Vec<isize>> {
+    // each section of the program reads one digit
+    let mut sections = (0..program.len())
+        .filter(|&i| program[i].operation == Operation::Input)
+        .collect::<Vec<_>>();
+    let start = sections.first().copied().unwrap_or(program.len());
+    let registers = evaluate(&program[..start], Registers::default(), &[]);
+    sections.push(program.len());
+
+    let digits = if maximize {
+        (1..=9).rev().collect::<Vec<isize>>()
+    } else {
+        (1..=9).collect::<Vec<isize>>()
+    };
+    let mut search = Search {
+        program,
+        live: sections.iter().map(|&start| live_registers(&program[start..])).collect(),
+        sections,
+        digits,
+        rejected: HashSet::new(),
+    };
+    let mut number = Vec::new();
+    search.find(0, registers, &mut number).then_some(number)
+}
+
+/// Depth-first search for the digits of an accepted number
+struct Search<'a> {
+    program: &'a [Instruction],
+    /// start of the program sections reading the digits, followed by the end
+    sections: Vec<usize>,
+    /// registers whose value matters at the start of each section
+    live: Vec<[bool; 4]>,
+    /// digits in order of preference
+    digits: Vec<isize>,
+    /// states known to not lead to an accepted number
+    rejected: HashSet<(usize, Registers)>,
+}
+
+impl Search<'_> {
+    fn find(&mut self, section: usize, registers: Registers, number: &mut Vec<isize>) -> bool {
+        if section + 1 == self.sections.len() {
+            return registers[3] == 0;
+        }
+        // values of dead registers don't affect the result
+        let live = self.live[section];
+        let registers: Registers = std::array::from_fn(|r| if live[r] { registers[r] } else { 0 });
+        if self.rejected.contains(&(section, registers)) {
+            return false;
+        }
+        let start = self.sections[section];
+        let z = bounds(&self.program[start..], registers)[3];
+        if !z.contains(0) {
+            trace::trace!("pruned {number:?} with z in {}..={}", z.min, z.max);
+            self.rejected.insert((section, registers));
+            return false;
+        }
+
+        let instructions = &self.program[start..self.sections[section + 1]];
+        for i in 0..self.digits.len() {
+            let digit = self.digits[i];
+            number.push(digit);
+            if self.find(section + 1, evaluate(instructions, registers, &[digit]), number) {
+                return true;
+            }
+            number.pop();
+        }
+        self.rejected.insert((section, registers));
+        false
+    }
+}
+
+fn evaluate(program: &[Instruction], registers: Registers, inputs: &[isize]) -> Registers {
+    trace::trace!("inputs: {:?}", inputs);
+    let mut alu = Machine::new(
+        program,
+        Alu {
+            registers,
+            inputs: VecDeque::from(inputs.to_vec()),
+        },
+    );
+    alu.run();
+    alu.state.registers
+}
+
+/// Registers read by the program before being written, assuming only `z` is used afterwards
+fn live_registers(program: &[Instruction]) -> [bool; 4] {
+    let mut live = [false, false, false, true];
+    for instruction in program.iter().rev() {
+        let operand = match instruction.operand {
+            Operand::Register(r) => Some(r),
+            _ => None,
+        };
+        live[instruction.register] = match (&instruction.operation, &instruction.operand) {
+            // result does not depend on the previous value
+            (Operation::Input, _) | (Operation::Multiply, Operand::Literal(0)) => false,
+            _ => true,
+        };
+        if let Some(r) = operand {
+            live[r] = true;
+        }
+    }
+    live
+}
+
+/// Ranges of register values after running the program with any digits as input
+fn bounds(program: &[Instruction], registers: Registers) -> [Range; 4] {
+    let mut ranges = registers.map(|value| Range { min: value, max: value });
+    for instruction in program {
+        let a = ranges[instruction.register];
+        let b = match instruction.operand {
+            Operand::Register(r) => ranges[r],
+            Operand::Literal(l) => Range { min: l, max: l },
+            Operand::None => Range { min: 0, max: 0 },
+        };
+        ranges[instruction.register] = match instruction.operation {
+            Operation::Input => Range { min: 1, max: 9 },
+            Operation::Add => Range {
+                min: a.min.saturating_add(b.min),
+                max: a.max.saturating_add(b.max),
+            },
+            Operation::Multiply => Range::hull(
+                [a.min, a.max].into_iter().flat_map(|x| [b.min, b.max].map(|y| x.saturating_mul(y))),
+            ),
+            Operation::Divide => {
+                // division truncates towards zero, divisor 0 is invalid
+                let divisors = [b.min, b.max, -1, 1].into_iter().filter(|&y| y != 0 && b.contains(y));
+                Range::hull(divisors.flat_map(|y| [a.min / y, a.max / y]))
+            }
+            Operation::Modulo => {
+                // result has the sign of the dividend
+                let limit = (b.min.unsigned_abs().max(b.max.unsigned_abs()) as isize - 1).max(0);
+                if a.min >= 0 && a.max < b.min {
+                    a
+                } else {
+                    Range {
+                        min: a.min.clamp(-limit, 0),
+                        max: a.max.clamp(0, limit),
+                    }
+                }
+            }
+            Operation::Equals => match (a.min == a.max && a == b, a.max < b.min || b.max < a.min) {
+                (true, _) => Range { min: 1, max: 1 },
+                (_, true) => Range { min: 0, max: 0 },
+                _ => Range { min: 0, max: 1 },
+            },
+        };
+    }
+    ranges
+}
+
+/// Inclusive range of values
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Range {
+    min: isize,
+    max: isize,
+}
+
+impl Range {
+    /// smallest range containing all values
+    fn hull(values: impl Iterator<Item = isize>) -> Self {
+        values.fold(Range { min: isize::MAX, max: isize::MIN }, |range, value| Range {
+            min: range.min.min(value),
+            max: range.max.max(value),
+        })
+    }
+
+    fn contains(&self, value: isize) -> bool {
+        (self.min..=self.max).contains(&value)
+    }
+}
+
+impl Decode<&str> for Instruction {
+    fn decode(line: &str) -> Self {
         let operation = match &line[0..3] {
             "inp" => Operation::Input,
             "add" => Operation::Add,
@@ -56,118 +247,73 @@ fn parse_input(input: &Vec<&str>) -> Program {
         } else {
             Operand::None
         };
-        program.push(Instruction {
+        Instruction {
             operation,
             register,
             operand,
-        });
+        }
     }
-    program
 }
 
-fn find_accepted_number(program: &[Instruction], maximize: bool) -> [isize; DIGIT_COUNT] {
-    let block_size = program.len() / DIGIT_COUNT;
-    let digits = if maximize {
-        (1..=9).rev().collect::<Vec<isize>>()
-    } else {
-        (1..=9).collect::<Vec<isize>>()
-    };
+impl vm::Instruction for Instruction {
+    type State = Alu;
 
-    let mut stack = Vec::new();
-    let mut number = [0isize; DIGIT_COUNT];
-
-    let mut i = 0;
-    while i < program.len() {
-        let type_instruction = &program[i + 4];
-        assert_eq!(type_instruction.operation, Operation::Divide);
-        assert_eq!(type_instruction.register, 3);
-        match type_instruction.operand {
-            Operand::Literal(1) => stack.push(i),
-            Operand::Literal(26) => {
-                let j = stack.pop().unwrap();
-                let block_pair =
-                    [&program[j..j + block_size], &program[i..i + block_size]].concat();
-                'outer: for a in &digits {
-                    for b in &digits {
-                        let z = evaluate(&block_pair, &[*a, *b])[3];
-                        if z == 0 {
-                            number[j / block_size] = *a;
-                            number[i / block_size] = *b;
-                            break 'outer;
-                        }
-                    }
+    fn execute(&self, alu: &mut Alu) -> Flow {
+        let registers = &mut alu.registers;
+        let operand = match self.operand {
+            Operand::Register(r) => registers[r],
+            Operand::Literal(l) => l,
+            Operand::None => 0,
+        };
+        let reg = &mut registers[self.register];
+        match self.operation {
+            Operation::Input => match alu.inputs.pop_front() {
+                Some(input) => *reg = input,
+                None => {
+                    trace::debug!("Warning: program expected more inputs");
+                    return Flow::Halt; // ran out of inputs
                 }
-            }
-            _ => unreachable!("unexpected instruction"),
+            },
+            Operation::Add => *reg += operand,
+            Operation::Multiply => *reg *= operand,
+            Operation::Divide => *reg /= operand,
+            Operation::Modulo => *reg %= operand,
+            Operation::Equals => *reg = (*reg == operand) as isize,
         }
-        i += block_size;
+        Flow::Next
     }
 
-    number
+    fn starts_block(&self) -> bool {
+        self.operation == Operation::Input
+    }
 }
 
-fn evaluate(program: &[Instruction], inputs: &[isize]) -> Registers {
-    let mut registers = Registers::default();
-    let mut i = 0;
-    #[cfg(test)]
-    println!("inputs: {:?}", inputs);
-    for instruction in program {
-        let registers_copy = registers.to_owned();
-        let reg = &mut registers[instruction.register];
-        #[cfg(test)]
-        let reg_char = (b'w' + instruction.register as u8) as char;
-        if instruction.operation == Operation::Input {
-            if i >= inputs.len() {
-                #[cfg(test)]
-                println!("Warning: program expected more than {i} inputs");
-                return registers; // ran out of inputs
-            }
-            #[cfg(test)]
-            println!("{} = {}", reg_char, inputs[i]);
-            *reg = inputs[i];
-            i += 1;
-        } else {
-            #[allow(unused_variables)]
-            let (operand, operand_str) = match instruction.operand {
-                Operand::Register(r) => (
-                    registers_copy[r],
-                    String::from_utf8(vec![b'w' + r as u8]).unwrap(),
-                ),
-                Operand::Literal(l) => (l, format!("{}", l)),
-                Operand::None => unreachable!(),
-            };
-            #[allow(unused_variables)]
-            let operation_char = match instruction.operation {
-                Operation::Input => unreachable!(),
-                Operation::Add => {
-                    *reg += operand;
-                    '+'
-                }
-                Operation::Multiply => {
-                    *reg *= operand;
-                    '*'
-                }
-                Operation::Divide => {
-                    *reg /= operand;
-                    '/'
-                }
-                Operation::Modulo => {
-                    *reg %= operand;
-                    '%'
-                }
-                Operation::Equals => {
-                    *reg = (registers_copy[instruction.register] == operand) as isize;
-                    '='
-                }
-            };
-            #[cfg(test)]
-            println!(
-                "{} {}= {} ({})",
-                reg_char, operation_char, operand_str, *reg
-            );
-        }
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reg_char = |r: usize| (b'w' + r as u8) as char;
+        let operand = match self.operand {
+            Operand::Register(r) => reg_char(r).to_string(),
+            Operand::Literal(l) => l.to_string(),
+            Operand::None => String::new(),
+        };
+        let reg = reg_char(self.register);
+        let operation = match self.operation {
+            Operation::Input => return write!(f, "{reg} = input"),
+            Operation::Equals => return write!(f, "{reg} = {reg} == {operand}"),
+            Operation::Add => '+',
+            Operation::Multiply => '*',
+            Operation::Divide => '/',
+            Operation::Modulo => '%',
+        };
+        write!(f, "{reg} {operation}= {operand}")
     }
-    registers
+}
+
+/// registers and remaining inputs of the ALU
+#[derive(Debug)]
+struct Alu {
+    registers: Registers,
+    inputs: VecDeque<isize>,
 }
 
 type Registers = [isize; 4]; // w|x|y|z
@@ -202,13 +348,13 @@ pub fn test() {
     let example1 = vec!["inp x", "mul x -1"];
     let program1 = parse_input(&example1);
     assert_eq!(program1.len(), 2);
-    assert_eq!(evaluate(&program1, &[2]), [0, -2, 0, 0]);
+    assert_eq!(evaluate(&program1, Registers::default(), &[2]), [0, -2, 0, 0]);
 
     let example2 = vec!["inp z", "inp x", "mul z 3", "eql z x"];
     let program2 = parse_input(&example2);
     assert_eq!(program2.len(), 4);
-    assert_eq!(evaluate(&program2, &[1, 3]), [0, 3, 0, 1]);
-    assert_eq!(evaluate(&program2, &[2, 5]), [0, 5, 0, 0]);
+    assert_eq!(evaluate(&program2, Registers::default(), &[1, 3]), [0, 3, 0, 1]);
+    assert_eq!(evaluate(&program2, Registers::default(), &[2, 5]), [0, 5, 0, 0]);
 
     let example3 = vec![
         "inp w", "add z w", "mod z 2", "div w 2", "add y w", "mod y 2", "div w 2", "add x w",
@@ -216,14 +362,41 @@ pub fn test() {
     ];
     let program3 = parse_input(&example3);
     assert_eq!(program3.len(), 11);
-    assert_eq!(evaluate(&program3, &[0]), [0, 0, 0, 0]);
-    assert_eq!(evaluate(&program3, &[1]), [0, 0, 0, 1]);
-    assert_eq!(evaluate(&program3, &[2]), [0, 0, 1, 0]);
-    assert_eq!(evaluate(&program3, &[4]), [0, 1, 0, 0]);
-    assert_eq!(evaluate(&program3, &[8]), [1, 0, 0, 0]);
-    assert_eq!(evaluate(&program3, &[15]), [1, 1, 1, 1]);
-    assert_eq!(evaluate(&program3, &[16]), [0, 0, 0, 0]);
-
-    // assert_eq!(a(&input), "");
-    // assert_eq!(b(&input), "");
+    assert_eq!(evaluate(&program3, Registers::default(), &[0]), [0, 0, 0, 0]);
+    assert_eq!(evaluate(&program3, Registers::default(), &[1]), [0, 0, 0, 1]);
+    assert_eq!(evaluate(&program3, Registers::default(), &[2]), [0, 0, 1, 0]);
+    assert_eq!(evaluate(&program3, Registers::default(), &[4]), [0, 1, 0, 0]);
+    assert_eq!(evaluate(&program3, Registers::default(), &[8]), [1, 0, 0, 0]);
+    assert_eq!(evaluate(&program3, Registers::default(), &[15]), [1, 1, 1, 1]);
+    assert_eq!(evaluate(&program3, Registers::default(), &[16]), [0, 0, 0, 0]);
+
+    // MONAD layout with parameters a/b/c per block
+    let monad = |blocks: &[(isize, isize, isize)]| {
+        blocks
+            .iter()
+            .flat_map(|(a, b, c)| {
+                [
+                    "inp w".to_owned(), "mul x 0".to_owned(), "add x z".to_owned(),
+                    "mod x 26".to_owned(), format!("div z {a}"), format!("add x {b}"),
+                    "eql x w".to_owned(), "eql x 0".to_owned(), "mul y 0".to_owned(),
+                    "add y 25".to_owned(), "mul y x".to_owned(), "add y 1".to_owned(),
+                    "mul z y".to_owned(), "mul y 0".to_owned(), "add y w".to_owned(),
+                    format!("add y {c}"), "mul y x".to_owned(), "add z y".to_owned(),
+                ]
+            })
+            .collect::<Vec<_>>()
+    };
+    let input = monad(&[
+        (1, 12, 6), (1, 11, 12), (1, 14, 5), (26, -8, 4), (1, 13, 7), (1, 10, 3), (26, -1, 9),
+        (1, 12, 7), (26, -15, 2), (1, 15, 15), (26, -11, 3), (26, -1, 8), (26, -10, 6), (26, -5, 5),
+    ]);
+    let input = input.iter().map(String::as_str).collect();
+    assert_eq!(a(&input), "87963799159999");
+    assert_eq!(b(&input), "11411139115732");
+
+    // other program shapes: digit sum of 10, preceded by setup
+    let input = vec!["add z -10", "inp w", "add z w", "inp w", "add z w"];
+    assert_eq!(a(&input), "91");
+    assert_eq!(b(&input), "19");
+    assert_eq!(find_accepted_number(&parse_input(&vec!["inp w", "add z 10"]), true), None);
 }
@@ -80,3 +80,11 @@ pub fn solver(day: u8, part: PartValues) -> SolveFunc {
         _ => unimplemented!("Unsupported puzzle: {day} {part:?}"),
     }
 }
+
+/// Disassembler of the puzzle input program, for days with a machine
+pub fn disassembler(day: u8) -> Option<SolveFunc> {
+    match day {
+        24 => Some(d24::disassemble),
+        _ => None,
+    }
+}
@@ -1,14 +1,23 @@
 //! Day 10: Cathode-Ray Tube
 
+use std::fmt;
+
+use crate::vm::{self, Decode, Flow, Machine};
+
 /// sum of products of register value and cycle number during certain cycles
 pub fn a(input: &Vec<&str>) -> String {
     let program = parse_input(input);
-    let mut vm = VirtualMachine::new();
+    let mut vm = Machine::new(&program, 1);
     let mut total = 0;
-    for cycle in 1..=220 {
-        let result = vm.execute_cycle(&program).unwrap();
+    while vm.cycle < 220 {
+        // register value during the cycle
+        let x = vm.state;
+        if !vm.tick() {
+            unreachable!("program ended early");
+        }
+        let cycle = vm.cycle as isize;
         if cycle >= 20 && (cycle - 20) % 40 == 0 {
-            total += cycle * result;
+            total += cycle * x;
         }
     }
     total.to_string()
@@ -17,12 +26,15 @@ pub fn a(input: &Vec<&str>) -> String {
 /// rendered image
 pub fn b(input: &Vec<&str>) -> String {
     let program = parse_input(input);
-    let mut vm = VirtualMachine::new();
+    let mut vm = Machine::new(&program, 1);
     let mut image = String::new();
 
-    for cycle in 1..=240 {
-        let pos = vm.execute_cycle(&program).unwrap();
-        let x = (cycle - 1) % 40;
+    while vm.cycle < 240 {
+        let pos = vm.state;
+        let x = (vm.cycle % 40) as isize;
+        if !vm.tick() {
+            unreachable!("program ended early");
+        }
         if x == 0 {
             image.push('\n');
         }
@@ -35,21 +47,13 @@ pub fn b(input: &Vec<&str>) -> String {
     image
 }
 
+/// program as annotated pseudo-code
+pub fn disassemble(input: &Vec<&str>) -> String {
+    vm::disassemble(&parse_input(input))
+}
+
 fn parse_input(input: &Vec<&str>) -> Program {
-    input
-        .iter()
-        .map(|&s| match &s[0..4] {
-            "addx" => Instruction {
-                operation: Operation::Addx,
-                value: s[5..].parse().unwrap(),
-            },
-            "noop" => Instruction {
-                operation: Operation::Noop,
-                value: 0,
-            },
-            _ => unreachable!("invalid instruction"),
-        })
-        .collect()
+    input.iter().map(|&line| Instruction::decode(line)).collect()
 }
 
 enum Operation {
@@ -64,39 +68,46 @@ struct Instruction {
 
 type Program = Vec<Instruction>;
 
-struct VirtualMachine {
-    reg_x: isize,
-    pending: bool,
-    ip: usize,
+impl Decode<&str> for Instruction {
+    fn decode(line: &str) -> Self {
+        match &line[0..4] {
+            "addx" => Instruction {
+                operation: Operation::Addx,
+                value: line[5..].parse().unwrap(),
+            },
+            "noop" => Instruction {
+                operation: Operation::Noop,
+                value: 0,
+            },
+            _ => unreachable!("invalid instruction"),
+        }
+    }
 }
 
-impl VirtualMachine {
-    fn new() -> Self {
-        Self {
-            reg_x: 1,
-            pending: false,
-            ip: 0,
+impl vm::Instruction for Instruction {
+    /// register X
+    type State = isize;
+
+    fn execute(&self, x: &mut isize) -> Flow {
+        if let Operation::Addx = self.operation {
+            *x += self.value;
         }
+        Flow::Next
     }
 
-    fn execute_cycle(&mut self, program: &Program) -> Result<isize, isize> {
-        if let Some(instruction) = program.get(self.ip) {
-            let result = self.reg_x;
-            match instruction.operation {
-                Operation::Addx => {
-                    if self.pending {
-                        self.reg_x += instruction.value;
-                        self.pending = false;
-                        self.ip += 1;
-                    } else {
-                        self.pending = true;
-                    }
-                }
-                Operation::Noop => self.ip += 1,
-            }
-            Ok(result)
-        } else {
-            Err(self.reg_x)
+    fn cycles(&self) -> usize {
+        match self.operation {
+            Operation::Addx => 2,
+            Operation::Noop => 1,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.operation {
+            Operation::Addx => write!(f, "x += {}", self.value),
+            Operation::Noop => write!(f, "nop"),
         }
     }
 }
@@ -123,13 +134,14 @@ pub fn test() {
     ];
 
     let program = parse_input(&vec!["noop", "addx 3", "addx -5"]);
-    let mut vm = VirtualMachine::new();
-    assert_eq!(vm.execute_cycle(&program), Ok(1));
-    assert_eq!(vm.execute_cycle(&program), Ok(1));
-    assert_eq!(vm.execute_cycle(&program), Ok(1));
-    assert_eq!(vm.execute_cycle(&program), Ok(4));
-    assert_eq!(vm.execute_cycle(&program), Ok(4));
-    assert_eq!(vm.execute_cycle(&program), Err(-1));
+    let mut vm = Machine::new(&program, 1);
+    let mut values = Vec::new();
+    while !vm.halted() {
+        values.push(vm.state);
+        vm.tick();
+    }
+    assert_eq!(values, [1, 1, 1, 4, 4]);
+    assert_eq!((vm.state, vm.cycle), (-1, 5));
 
     assert_eq!(a(&input), "13140");
     assert_eq!(
@@ -2,6 +2,8 @@
 
 use std::collections::VecDeque;
 
+use crate::utils;
+
 /// product of number of inspected items by 2 most active monkeys after 20 rounds
 pub fn a(input: &Vec<&str>) -> String {
     let mut monkeys = parse_input(input);
@@ -52,7 +54,7 @@ fn parse_input(input: &Vec<&str>) -> Vec<Monkey> {
 }
 
 fn play_round(monkeys: &mut Vec<Monkey>, relief: bool) {
-    let test_multiple: usize = monkeys.iter().map(|m| m.test).product();  // lcm?
+    let test_multiple = utils::lcm(&monkeys.iter().map(|m| m.test).collect::<Vec<_>>());
     for i in 0..monkeys.len() {
         monkeys[i].count += monkeys[i].items.len();
         while let Some(mut item) = monkeys[i].items.pop_front() {
@@ -1,35 +1,33 @@
 //! Day 13: Distress Signal
 
-use std::cmp::Ordering;
+use std::{cmp::Ordering, fmt, str::FromStr};
 
 /// sum of indices of pairs in right order
 pub fn a(input: &Vec<&str>) -> String {
-    let packets = parse_input(input);
-    packets
+    let lines = input.iter().filter(|line| !line.is_empty()).collect::<Vec<_>>();
+    lines
         .chunks_exact(2)
         .enumerate()
-        .filter_map(|(i, pair)| compare(&pair[0], &pair[1]).is_lt().then(|| i + 1))
+        .filter_map(|(i, pair)| compare_str(pair[0], pair[1]).is_lt().then_some(i + 1))
         .sum::<usize>()
         .to_string()
 }
 
 /// product of indices of divider packets in sorted packet list
 pub fn b(input: &Vec<&str>) -> String {
-    let mut packets = parse_input(input);
-
-    let divider2 = tokenize("[[2]]");
-    let divider6 = tokenize("[[6]]");
-    packets.push(divider2.to_owned());
-    packets.push(divider6.to_owned());
-    packets.sort_unstable_by(compare);
+    let dividers = ["[[2]]", "[[6]]"].map(|s| (s.parse::<Packet>().unwrap(), false));
+    let mut packets = parse_input(input)
+        .into_iter()
+        .map(|packet| (packet, true))
+        .chain(dividers)
+        .collect::<Vec<_>>();
+    // dividers are flagged as `false`, so they sort before packets equal to them
+    packets.sort_unstable();
 
     packets
         .iter()
         .enumerate()
-        .filter_map(|(i, packet)| {
-            (compare(packet, &divider2).is_eq() || compare(packet, &divider6).is_eq())
-                .then(|| i + 1)
-        })
+        .filter_map(|(i, (_, from_input))| (!from_input).then_some(i + 1))
         .product::<usize>()
         .to_string()
 }
@@ -37,80 +35,211 @@ pub fn b(input: &Vec<&str>) -> String {
 fn parse_input(input: &Vec<&str>) -> Vec<Packet> {
     input
         .iter()
-        .filter_map(|line| line.is_empty().eq(&false).then(|| tokenize(line)))
+        .filter(|line| !line.is_empty())
+        .map(|line| line.parse().unwrap())
         .collect()
 }
 
-fn tokenize(s: &str) -> Packet {
-    let mut packet = Packet::new();
-    let mut digits = Vec::new();
+/// Packet data, an integer or a list of packets
+#[derive(Clone, Debug)]
+pub enum Packet {
+    Integer(u32),
+    List(Vec<Packet>),
+}
 
-    for c in s.chars() {
-        match c {
-            '[' => packet.push(Token::Open),
-            ']' | ',' => {
-                if !digits.is_empty() {
-                    packet.push(Token::Literal(
-                        digits.iter().collect::<String>().parse().unwrap(),
-                    ));
-                    digits = Vec::new();
+impl Packet {
+    /// parses the packet starting at `bytes[*i]`, advancing `*i` past it
+    fn parse(bytes: &[u8], i: &mut usize) -> Result<Self, &'static str> {
+        match bytes.get(*i) {
+            Some(b'[') => {
+                *i += 1;
+                let mut packets = Vec::new();
+                if bytes.get(*i) == Some(&b']') {
+                    *i += 1;
+                    return Ok(Packet::List(packets));
                 }
-                if c == ']' {
-                    packet.push(Token::Close);
+                loop {
+                    packets.push(Packet::parse(bytes, i)?);
+                    *i += 1;
+                    match bytes.get(*i - 1) {
+                        Some(b',') => (),
+                        Some(b']') => return Ok(Packet::List(packets)),
+                        Some(_) => return Err("invalid character"),
+                        None => return Err("unexpected end"),
+                    }
                 }
             }
-            '0'..='9' => digits.push(c),
-            _ => unreachable!("invalid character"),
+            Some(b'0'..=b'9') => {
+                let (value, len) = parse_integer(&bytes[*i..]);
+                *i += len;
+                Ok(Packet::Integer(value))
+            }
+            Some(_) => Err("invalid character"),
+            None => Err("unexpected end"),
         }
     }
+}
 
-    packet
-}
-
-fn compare(left: &Packet, right: &Packet) -> Ordering {
-    // turn into mutable stacks
-    let mut left = left.iter().rev().collect::<Vec<_>>();
-    let mut right = right.iter().rev().collect::<Vec<_>>();
-
-    while let (Some(l), Some(r)) = (left.pop(), right.pop()) {
-        let left_closes = *l == Token::Close;
-        let right_closes = *r == Token::Close;
-
-        if left_closes && !right_closes {
-            return Ordering::Less;
-        } else if right_closes && !left_closes {
-            return Ordering::Greater;
-        } else if left_closes && right_closes {
-            // ignore equality
-        } else {
-            match (l, r) {
-                (Token::Open, Token::Literal(_)) => {
-                    right.push(&Token::Close);
-                    right.push(r);
-                }
-                (Token::Literal(_), Token::Open) => {
-                    left.push(&Token::Close);
-                    left.push(l);
-                }
-                (Token::Literal(x), Token::Literal(y)) if x != y => {
-                    return x.cmp(y);
+impl FromStr for Packet {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut i = 0;
+        let packet = Packet::parse(s.as_bytes(), &mut i)?;
+        if i != s.len() {
+            return Err("trailing characters");
+        }
+        Ok(packet)
+    }
+}
+
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Packet::Integer(value) => write!(f, "{value}"),
+            Packet::List(packets) => {
+                write!(f, "[")?;
+                for (i, packet) in packets.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{packet}")?;
                 }
-                _ => {}
+                write!(f, "]")
             }
         }
     }
+}
+
+/// Integers compare by value, lists element-wise and then by length, and an
+/// integer compared to a list is treated as a list containing only the integer
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Packet::Integer(left), Packet::Integer(right)) => left.cmp(right),
+            (Packet::List(left), Packet::List(right)) => left.cmp(right),
+            (Packet::Integer(_), Packet::List(right)) => std::slice::from_ref(self).cmp(right),
+            (Packet::List(left), Packet::Integer(_)) => left.as_slice().cmp(std::slice::from_ref(other)),
+        }
+    }
+}
 
-    Ordering::Equal
+/// Packets are equal if they are in the same place of the order, e.g. `[[1],2]`
+/// and `[[[1]],2]`
+impl PartialEq for Packet {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Packet {}
+
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares packets in their text form like [`Packet::cmp`], without parsing
+/// them into trees
+pub fn compare_str(left: &str, right: &str) -> Ordering {
+    let mut left = Cursor::new(left);
+    let mut right = Cursor::new(right);
+
+    loop {
+        match (left.peek(), right.peek()) {
+            (None, None) => return Ordering::Equal,
+            (Some(Token::Open), Some(Token::Open)) | (Some(Token::Close), Some(Token::Close)) => {
+                left.advance();
+                right.advance();
+            }
+            (Some(Token::Close), _) | (None, _) => return Ordering::Less,
+            (_, Some(Token::Close)) | (_, None) => return Ordering::Greater,
+            (Some(Token::Integer(x)), Some(Token::Integer(y))) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                left.advance();
+                right.advance();
+            }
+            // integer is compared as a list containing only the integer
+            (Some(Token::Integer(_)), Some(Token::Open)) => {
+                left.wrap();
+                right.advance();
+            }
+            (Some(Token::Open), Some(Token::Integer(_))) => {
+                left.advance();
+                right.wrap();
+            }
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum Token {
     Open,
     Close,
-    Literal(u8),
+    Integer(u32),
+}
+
+/// Token stream of a packet's text, integers can be wrapped into lists on the fly
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    /// lists the current integer is wrapped into
+    wraps: usize,
+    /// pending closing brackets of a wrapped integer
+    closes: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(s: &'a str) -> Self {
+        Self { bytes: s.as_bytes(), pos: 0, wraps: 0, closes: 0 }
+    }
+
+    fn peek(&mut self) -> Option<Token> {
+        if self.closes > 0 {
+            return Some(Token::Close);
+        }
+        while self.bytes.get(self.pos) == Some(&b',') {
+            self.pos += 1;
+        }
+        let token = match self.bytes.get(self.pos)? {
+            b'[' => Token::Open,
+            b']' => Token::Close,
+            b'0'..=b'9' => Token::Integer(parse_integer(&self.bytes[self.pos..]).0),
+            _ => unreachable!("invalid character"),
+        };
+        Some(token)
+    }
+
+    fn advance(&mut self) {
+        if self.closes > 0 {
+            self.closes -= 1;
+            return;
+        }
+        match self.peek() {
+            Some(Token::Integer(_)) => {
+                self.pos += parse_integer(&self.bytes[self.pos..]).1;
+                self.closes = std::mem::take(&mut self.wraps);
+            }
+            Some(_) => self.pos += 1,
+            None => (),
+        }
+    }
+
+    /// treats the current integer as a list containing only the integer
+    fn wrap(&mut self) {
+        self.wraps += 1;
+    }
 }
 
-type Packet = Vec<Token>;
+/// value and length of the leading integer
+fn parse_integer(bytes: &[u8]) -> (u32, usize) {
+    let len = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+    let value = bytes[..len].iter().fold(0, |value, b| value * 10 + (b - b'0') as u32);
+    (value, len)
+}
 
 #[test]
 pub fn test() {
@@ -140,80 +269,69 @@ pub fn test() {
         "[1,[2,[3,[4,[5,6,0]]]],8,9]",
     ];
 
+    let packet = input[3].parse::<Packet>().unwrap();
     assert_eq!(
-        tokenize(&input[3]),
-        vec![
-            Token::Open,
-            Token::Open,
-            Token::Literal(1),
-            Token::Close,
-            Token::Open,
-            Token::Literal(2),
-            Token::Literal(3),
-            Token::Literal(4),
-            Token::Close,
-            Token::Close,
-        ]
+        packet,
+        Packet::List(vec![
+            Packet::List(vec![Packet::Integer(1)]),
+            Packet::List(vec![Packet::Integer(2), Packet::Integer(3), Packet::Integer(4)]),
+        ])
     );
-
+    assert_eq!(packet.to_string(), input[3]);
     assert_eq!(
-        tokenize("[1,10,7,10]"),
-        vec![
-            Token::Open,
-            Token::Literal(1),
-            Token::Literal(10),
-            Token::Literal(7),
-            Token::Literal(10),
-            Token::Close,
-        ]
+        "[1,10,7,10]".parse::<Packet>(),
+        Ok(Packet::List(vec![
+            Packet::Integer(1),
+            Packet::Integer(10),
+            Packet::Integer(7),
+            Packet::Integer(10),
+        ]))
     );
+    assert_eq!("[1,[2]".parse::<Packet>(), Err("unexpected end"));
+    assert_eq!("[1]]".parse::<Packet>(), Err("trailing characters"));
+    assert_eq!("[a]".parse::<Packet>(), Err("invalid character"));
 
-    assert_eq!(
-        compare(&tokenize(&input[0]), &tokenize(&input[1])),
-        Ordering::Less
-    );
-    assert_eq!(
-        compare(&tokenize(&input[3]), &tokenize(&input[4])),
-        Ordering::Less
-    );
-    assert_eq!(
-        compare(&tokenize(&input[6]), &tokenize(&input[7])),
-        Ordering::Greater
-    );
-    assert_eq!(
-        compare(&tokenize(&input[9]), &tokenize(&input[10])),
-        Ordering::Less
-    );
-    assert_eq!(
-        compare(&tokenize(&input[12]), &tokenize(&input[13])),
-        Ordering::Greater
-    );
-    assert_eq!(
-        compare(&tokenize(&input[15]), &tokenize(&input[16])),
-        Ordering::Less
-    );
-    assert_eq!(
-        compare(&tokenize(&input[18]), &tokenize(&input[19])),
-        Ordering::Greater
-    );
-    assert_eq!(
-        compare(&tokenize(&input[21]), &tokenize(&input[22])),
-        Ordering::Greater
-    );
+    // both comparators agree on all pairs of lines
+    let compare = |left: &str, right: &str| {
+        let ordering = compare_str(left, right);
+        assert_eq!(left.parse::<Packet>().unwrap().cmp(&right.parse().unwrap()), ordering);
+        ordering
+    };
+    let lines = input.iter().filter(|line| !line.is_empty()).collect::<Vec<_>>();
+    for left in &lines {
+        for right in &lines {
+            compare(left, right);
+        }
+    }
 
+    assert_eq!(compare(input[0], input[1]), Ordering::Less);
+    assert_eq!(compare(input[3], input[4]), Ordering::Less);
+    assert_eq!(compare(input[6], input[7]), Ordering::Greater);
+    assert_eq!(compare(input[9], input[10]), Ordering::Less);
+    assert_eq!(compare(input[12], input[13]), Ordering::Greater);
+    assert_eq!(compare(input[15], input[16]), Ordering::Less);
+    assert_eq!(compare(input[18], input[19]), Ordering::Greater);
+    assert_eq!(compare(input[21], input[22]), Ordering::Greater);
     assert_eq!(
         compare(
-            &tokenize("[[6],[7],[9,[0,[8,6]],[9]]]"),
-            &tokenize("[[[[6],[],[2,10],0],[8,[4,10],[4,5,8,0,0]]],[3,1],[[10]]]")
+            "[[6],[7],[9,[0,[8,6]],[9]]]",
+            "[[[[6],[],[2,10],0],[8,[4,10],[4,5,8,0,0]]],[3,1],[[10]]]"
         ),
         Ordering::Less
     );
-
-    assert_eq!(
-        compare(&tokenize("[1,[2,[3,4]]]"), &tokenize("[1,[2,[3,4]]]")),
-        Ordering::Equal
-    );
+    assert_eq!(compare("[1,[2,[3,4]]]", "[1,[2,[3,4]]]"), Ordering::Equal);
+    // wrapped integers equal to a nested list
+    assert_eq!(compare("[[1],2]", "[[[1]],2]"), Ordering::Equal);
+    assert_eq!("[[1],2]".parse::<Packet>().unwrap(), "[[[1]],2]".parse().unwrap());
+    assert_ne!("[[1],2]".parse::<Packet>().unwrap(), "[[1],3]".parse().unwrap());
+    assert_eq!(compare("[1,2]", "[[[1]],2,3]"), Ordering::Less);
+    assert_eq!(compare("[[[1]],3]", "[1,2]"), Ordering::Greater);
 
     assert_eq!(a(&input), "13");
     assert_eq!(b(&input), "140");
+
+    // packets equal to a divider sort next to it without shifting it
+    let mut input = input.clone();
+    input.extend(["", "[2]", "[[[2]]]", "[[6],[]]"]);
+    assert_eq!(b(&input), "160");
 }
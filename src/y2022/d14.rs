@@ -1,19 +1,17 @@
 //! Day 14: Regolith Reservoir
 
+use crate::visual::{self, Frame};
+
 /// number of simulation steps until overflow
 pub fn a(input: &Vec<&str>) -> String {
     let (mut grid, emitter) = parse_input(input, false);
-    let mut steps = 0usize;
-    while simulate(emitter, &mut grid) { steps += 1; }
-    steps.to_string()
+    fill(emitter, &mut grid).to_string()
 }
 
 /// number of simulation steps until source blocked
 pub fn b(input: &Vec<&str>) -> String {
     let (mut grid, emitter) = parse_input(input, true);
-    let mut steps = 0usize;
-    while simulate(emitter, &mut grid) { steps += 1; }
-    steps.to_string()
+    fill(emitter, &mut grid).to_string()
 }
 
 fn parse_input(input: &Vec<&str>, infinite: bool) -> (Grid, usize) {
@@ -63,6 +61,30 @@ fn draw_line(p1: &Point, p2: &Point, x_offset: usize, grid: &mut Grid) {
     }
 }
 
+/// emits sand units until overflowing or blocked, returns number of resting units
+fn fill(emitter: usize, grid: &mut Grid) -> usize {
+    // rocks are only kept to tell them apart from sand when rendering
+    let rocks = visual::enabled().then(|| grid.clone());
+    let mut steps = 0usize;
+    while simulate(emitter, grid) {
+        steps += 1;
+        if let Some(rocks) = &rocks {
+            visual::emit(|| render(grid, rocks).with_caption(format!("{steps} units")));
+        }
+    }
+    steps
+}
+
+fn render(grid: &Grid, rocks: &Grid) -> Frame {
+    Frame::from_rows(grid.iter().zip(rocks).map(|(row, rocks)| {
+        row.iter().zip(rocks).map(|(&filled, &rock)| match (filled, rock) {
+            (_, true) => b'#',
+            (true, false) => b'O',
+            _ => b'.',
+        })
+    }))
+}
+
 /// emits a single sand unit and simulates flow until rest or boundary reached
 fn simulate(emitter: usize, grid: &mut Grid) -> bool {
     let mut p: Point = (emitter, 0);
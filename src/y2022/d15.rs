@@ -2,6 +2,8 @@
 
 use std::collections::BTreeSet;
 
+use crate::utils::interval::{Interval, IntervalSet};
+
 /// number of positions excluded to contain a beacon
 pub fn a(input: &Vec<&str>) -> String {
     let report = parse_input(input);
@@ -48,40 +50,19 @@ fn distance(p1: &Point, p2: &Point) -> isize {
     (p1.0.abs_diff(p2.0) + p1.1.abs_diff(p2.1)) as isize
 }
 
-/// determines intersections of row with sensor area (beacon exclusion zone)
-fn get_sensor_intersections(report: &Report, row: isize) -> Vec<(isize, bool)> {
-    let mut intersections = Vec::with_capacity(report.len() * 2);
-    for data in report {
-        let range = data.radius - (data.sensor.1 - row).abs();
-        if range >= 0 {
-            intersections.push((data.sensor.0 - range, true));  // start
-            intersections.push((data.sensor.0 + range, false));  // end
-        }
-    }
-    intersections.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
-    intersections
+/// determines intersections of row with sensor areas (beacon exclusion zone)
+fn get_sensor_intersections(report: &Report, row: isize) -> IntervalSet<isize> {
+    report
+        .iter()
+        .map(|data| {
+            let range = data.radius - (data.sensor.1 - row).abs();
+            Interval::inclusive(data.sensor.0 - range, data.sensor.0 + range)
+        })
+        .collect()
 }
 
 fn count_beacon_exclusion(report: &Report, row: isize) -> usize {
-    let intersections = get_sensor_intersections(report, row);
-
-    let mut exclusion_count = 0usize;
-    let mut overlaps = 0usize;
-    let mut start = isize::MAX;
-
-    for (x, inside) in &intersections {
-        if *inside {
-            if overlaps == 0 {
-                start = *x;
-            }
-            overlaps += 1;
-        } else {
-            overlaps -= 1;
-            if overlaps == 0 {
-                exclusion_count += (*x - start + 1) as usize;
-            }
-        }
-    }
+    let exclusion_count = get_sensor_intersections(report, row).len() as usize;
 
     let beacons_in_row = report
         .iter()
@@ -93,27 +74,14 @@ fn count_beacon_exclusion(report: &Report, row: isize) -> usize {
 }
 
 fn find_hidden_beacon(report: &Report, max_coord: isize) -> Point {
+    let area = IntervalSet::from_iter([Interval::inclusive(0, max_coord)]);
     for row in 0..=max_coord {  // (this is slow)
-        let intersections = get_sensor_intersections(report, row);
-
-        let mut overlaps = 0usize;
-        let mut end = isize::MAX;
-
-        for (x, inside) in &intersections {
-            if *inside {
-                if overlaps == 0 && end < *x - 1 {
-                    return (x - 1, row);
-                }
-                overlaps += 1;
-            } else {
-                overlaps -= 1;
-                if overlaps == 0 {
-                    end = *x;
-                }
-            }
+        let uncovered = area.difference(&get_sensor_intersections(report, row));
+        if let Some(gap) = uncovered.intervals().first() {
+            return (gap.start, row);
         }
     }
-    
+
     unreachable!("failed to find hidden beacon");
 }
 
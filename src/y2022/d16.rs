@@ -1,8 +1,9 @@
 //! Day 16: Proboscidea Volcanium
 
+use crate::dot;
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::collections::{HashMap, HashSet};
+use crate::utils::hash::{FastHashMap, FastHashSet};
 
 const START: &str = "AA";
 
@@ -83,6 +84,28 @@ fn parse_input<'a>(input: &Vec<&'a str>) -> (Graph<'a>, DistanceCache<'a>) {
         })
         .collect();
 
+    dot::export(|| {
+        let mut dot = dot::Graph::undirected("tunnels");
+        for line in input {
+            let id = RE.captures(line).unwrap().get(1).unwrap().as_str();
+            let valve = &graph[id];
+            dot.node(id)
+                .kind(match valve.rate {
+                    _ if id == START => "start",
+                    0 => "broken",
+                    _ => "valve",
+                })
+                .label(format!("{id} ({})", valve.rate));
+        }
+        for line in input {
+            let id = RE.captures(line).unwrap().get(1).unwrap().as_str();
+            for adj in graph[id].adjacent.keys().filter(|&&adj| id < adj) {
+                dot.edge(id, adj);
+            }
+        }
+        dot
+    });
+
     // remove valves with zero flow rate
     let useless_ids = graph
         .iter()
@@ -103,7 +126,7 @@ fn parse_input<'a>(input: &Vec<&'a str>) -> (Graph<'a>, DistanceCache<'a>) {
     }
 
     // cache distances between all remaining valves
-    let mut distances = HashMap::<(&'a str, &'a str), usize>::new();
+    let mut distances = DistanceCache::default();
     for (id, valve) in &graph {
         distances.insert((id, id), 0);
         for (adj_id, adj_dist) in &valve.adjacent {
@@ -136,7 +159,7 @@ fn find_total_pressure<'a>(
     let all_valves = graph
         .iter()
         .filter_map(|(id, v)| v.rate.gt(&0).then(|| *id))
-        .collect::<HashSet<_>>();
+        .collect::<FastHashSet<_>>();
     let mut states = vec![State {
         pos: START,
         unopened: all_valves.to_owned(),
@@ -220,15 +243,15 @@ fn find_total_pressure<'a>(
 #[derive(Debug)]
 struct Valve<'a> {
     rate: usize,
-    adjacent: HashMap<&'a str, usize>,
+    adjacent: FastHashMap<&'a str, usize>,
 }
 
-type Graph<'a> = HashMap<&'a str, Valve<'a>>;
-type DistanceCache<'a> = HashMap<(&'a str, &'a str), usize>;
+type Graph<'a> = FastHashMap<&'a str, Valve<'a>>;
+type DistanceCache<'a> = FastHashMap<(&'a str, &'a str), usize>;
 
 struct State<'a> {
     pos: &'a str,
-    unopened: HashSet<&'a str>,
+    unopened: FastHashSet<&'a str>,
     remaining: usize,
     total_pressure: usize,
 }
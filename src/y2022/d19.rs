@@ -1,14 +1,15 @@
 //! Day 19: Not Enough Minerals
 
+use crate::utils::parallel::par_map;
 use std::str::FromStr;
 
 /// sum of products of blueprint ID and max number of geodes in 24 mins
 pub fn a(input: &Vec<&str>) -> String {
     let blueprints = parse_input(input);
-    blueprints
+    par_map(&blueprints, |b| simulate(b, 24))
         .iter()
         .enumerate()
-        .map(|(i, b)| (i + 1) * simulate(b, 24))
+        .map(|(i, geodes)| (i + 1) * geodes)
         .sum::<usize>()
         .to_string()
 }
@@ -16,10 +17,8 @@ pub fn a(input: &Vec<&str>) -> String {
 /// product of max number of geodes of first 3 blueprints in 32 mins
 pub fn b(input: &Vec<&str>) -> String {
     let blueprints = parse_input(input);
-    blueprints
+    par_map(&blueprints[..blueprints.len().min(3)], |b| simulate(b, 32))
         .iter()
-        .take(3)
-        .map(|b| simulate(b, 32))
         .product::<usize>()
         .to_string()
 }
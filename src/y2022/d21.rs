@@ -1,6 +1,12 @@
 //! Day 21: Monkey Math
 
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::HashMap,
+    ops::{Add, Sub},
+    str::FromStr,
+};
+
+use crate::utils::linalg::Rational;
 
 /// Result of `root` equation
 pub fn a(input: &Vec<&str>) -> String {
@@ -11,10 +17,8 @@ pub fn a(input: &Vec<&str>) -> String {
 /// Value for `humn` node with equality at `root`
 pub fn b(input: &Vec<&str>) -> String {
     let monkeys = parse_input(input);
-    let mut variable_path = Vec::new();
-    get_variable_path("root", &monkeys, &mut variable_path);
-    // println!("variable path: {variable_path:?}");
-    let value = inverse_solve("root", &0, variable_path, &monkeys);
+    let value = solve_for_humn(&monkeys).unwrap_or_else(|err| panic!("cannot solve for humn: {err}"));
+    assert_eq!(value.den, 1, "solution {value} is not an integer");
     value.to_string()
 }
 
@@ -58,69 +62,100 @@ fn solve(monkey: &str, monkeys: &Monkeys) -> isize {
     }
 }
 
-fn inverse_solve(monkey: &str, result: &isize, mut path: Vec<&str>, monkeys: &Monkeys) -> isize {
-    if let Some(var) = path.pop() {
-        match &monkeys[monkey] {
-            MonkeyJob::Literal(_) => unreachable!(),
-            MonkeyJob::Equation {
-                op1,
-                op2,
-                operation,
-            } => {
-                let other = if *op1 == var { *op2 } else { *op1 };
-                let sub_result = solve(other, monkeys);
-                let var_result = if monkey == "root" {
-                    sub_result
-                } else if *op1 == var {
-                    // result = var_result ? sub_result
-                    match operation {
-                        Operation::Add => result - sub_result,
-                        Operation::Subtract => result + sub_result,
-                        Operation::Multiply => result / sub_result,
-                        Operation::Divide => result * sub_result,
-                    }
-                } else if *op2 == var {
-                    // result = sub_result ? var_result
-                    match operation {
-                        Operation::Add => result - sub_result,
-                        Operation::Subtract => sub_result - result,
-                        Operation::Multiply => result / sub_result,
-                        Operation::Divide => sub_result / result,
-                    }
-                } else {
-                    unreachable!("no variable in equation");
-                };
-                return inverse_solve(var, &var_result, path, monkeys);
-            }
-        }
-    } else if monkey == "humn" {
-        return *result;
+/// Solves the equality at `root` for `humn`, which has to appear linearly
+fn solve_for_humn(monkeys: &Monkeys) -> Result<Rational, &'static str> {
+    let MonkeyJob::Equation { op1, op2, .. } = &monkeys["root"] else {
+        return Err("root is not an equation");
+    };
+    let equation = symbolic(op1, monkeys)? - symbolic(op2, monkeys)?;
+    // slope * humn + offset = 0
+    if equation.slope == Rational::from(0) {
+        return Err("humn does not determine the result");
     }
-    unreachable!();
+    Ok(-equation.offset / equation.slope)
 }
 
-/// finds the `humn` variable in the operation tree and builds a path to it
-fn get_variable_path<'a>(monkey: &str, monkeys: &Monkeys<'a>, path: &mut Vec<&'a str>) -> bool {
+/// Linear expression in `humn` computed by the monkey
+fn symbolic(monkey: &str, monkeys: &Monkeys) -> Result<Linear, &'static str> {
+    if monkey == "humn" {
+        return Ok(Linear {
+            slope: Rational::from(1),
+            offset: Rational::from(0),
+        });
+    }
     match &monkeys[monkey] {
-        MonkeyJob::Literal(_) => monkey == "humn",
+        MonkeyJob::Literal(val) => Ok(Linear::constant(Rational::from(*val as i128))),
         MonkeyJob::Equation {
             op1,
             op2,
-            operation: _,
+            operation,
         } => {
-            if get_variable_path(op1, monkeys, path) {
-                path.push(op1);
-                true
-            } else if get_variable_path(op2, monkeys, path) {
-                path.push(op2);
-                true
-            } else {
-                false
+            let a = symbolic(op1, monkeys)?;
+            let b = symbolic(op2, monkeys)?;
+            match operation {
+                Operation::Add => Ok(a + b),
+                Operation::Subtract => Ok(a - b),
+                Operation::Multiply => match (a.as_constant(), b.as_constant()) {
+                    (Some(factor), _) => Ok(b.scale(factor)),
+                    (_, Some(factor)) => Ok(a.scale(factor)),
+                    _ => Err("humn appears in a product with itself"),
+                },
+                Operation::Divide => match b.as_constant() {
+                    Some(divisor) if divisor == Rational::from(0) => Err("division by zero"),
+                    Some(divisor) => Ok(a.scale(Rational::from(1) / divisor)),
+                    None => Err("humn appears in a divisor"),
+                },
             }
         }
     }
 }
 
+/// Expression `slope * humn + offset`
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Linear {
+    slope: Rational,
+    offset: Rational,
+}
+
+impl Linear {
+    fn constant(offset: Rational) -> Self {
+        Self {
+            slope: Rational::from(0),
+            offset,
+        }
+    }
+
+    fn as_constant(&self) -> Option<Rational> {
+        (self.slope == Rational::from(0)).then_some(self.offset)
+    }
+
+    fn scale(self, factor: Rational) -> Self {
+        Self {
+            slope: self.slope * factor,
+            offset: self.offset * factor,
+        }
+    }
+}
+
+impl Add for Linear {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            slope: self.slope + other.slope,
+            offset: self.offset + other.offset,
+        }
+    }
+}
+
+impl Sub for Linear {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + other.scale(Rational::from(-1))
+    }
+}
+
 enum Operation {
     Add,
     Subtract,
@@ -175,4 +210,17 @@ pub fn test() {
 
     assert_eq!(a(&input), "152");
     assert_eq!(b(&input), "301");
+
+    // humn used on both sides
+    let mut input2 = input.clone();
+    input2[14] = "hmdt: humn + tsvn";
+    input2.push("tsvn: 27");
+    let monkeys = parse_input(&input2);
+    assert_eq!(solve_for_humn(&monkeys), Ok(Rational::new(-251, 9)));
+    input2[12] = "lgvd: ljgn / ptdq";
+    let monkeys = parse_input(&input2);
+    assert_eq!(solve_for_humn(&monkeys), Err("humn appears in a divisor"));
+    input2[12] = "lgvd: hmdt * ptdq";
+    let monkeys = parse_input(&input2);
+    assert_eq!(solve_for_humn(&monkeys), Err("humn appears in a product with itself"));
 }
@@ -1,62 +1,67 @@
 //! Day 23: Unstable Diffusion
 
-use std::collections::{HashMap, HashSet};
+use crate::{
+    utils::{bitset::BitGrid, hash::FastHashMap},
+    visual::{self, Frame},
+};
 
 /// Number of empty positions in bounding rectangle
 pub fn a(input: &Vec<&str>) -> String {
-    let mut points = parse_input(input);
-    simulate(&mut points, 10);
-    let (min, max) = points.iter().fold(
-        ((isize::MAX, isize::MAX), (isize::MIN, isize::MIN)),
-        |acc, p| {
-            (
-                (acc.0 .0.min(p.0), acc.0 .1.min(p.1)),
-                (acc.1 .0.max(p.0), acc.1 .1.max(p.1)),
-            )
-        },
-    );
-    let area = ((max.0 + 1 - min.0) * (max.1 + 1 - min.1)) as usize;
-    (area - points.len()).to_string()
+    let mut elves = parse_input(input);
+    simulate(&mut elves, 10);
+    let (min, max) = elves.bounds();
+    let area = (max.0 + 1 - min.0) * (max.1 + 1 - min.1);
+    (area - elves.positions.len()).to_string()
 }
 
 /// Number of first round with equilibrium state
 pub fn b(input: &Vec<&str>) -> String {
-    let mut points = parse_input(input);
-    let rounds = simulate(&mut points, 1000000);
+    let mut elves = parse_input(input);
+    let rounds = simulate(&mut elves, 1000000);
     rounds.to_string()
 }
 
-fn parse_input(input: &Vec<&str>) -> PointSet {
-    let mut points = PointSet::new();
+fn parse_input(input: &Vec<&str>) -> Elves {
+    let mut elves = Elves {
+        origin: 0,
+        positions: Vec::new(),
+        grid: BitGrid::new(input[0].len(), input.len()),
+    };
     for (y, line) in input.iter().enumerate() {
         for (x, b) in line.bytes().enumerate() {
             if b == b'#' {
-                points.insert((x as isize, y as isize));
+                elves.positions.push((x, y));
+                elves.grid.insert(x, y);
             }
         }
     }
-    points
+    elves
 }
 
-fn simulate(positions: &mut PointSet, max_rounds: usize) -> usize {
-    const DIRECTIONS: [Point; 4] = [
+fn simulate(elves: &mut Elves, max_rounds: usize) -> usize {
+    const DIRECTIONS: [(isize, isize); 4] = [
         (0, -1), // N
         (0, 1),  // S
         (-1, 0), // W
         (1, 0),  // E
     ];
     let mut start_dir = 0usize;
+    let mut proposed = PointMap::with_capacity_and_hasher(elves.positions.len(), Default::default());
 
     for round in 0..max_rounds {
-        let mut proposed = PointMap::with_capacity(positions.len());
-
-        for pos in positions.iter() {
+        elves.ensure_border();
+        let grid = &elves.grid;
+        let occupied = |pos: Point, dx: isize, dy: isize| {
+            grid.contains(pos.0.wrapping_add_signed(dx), pos.1.wrapping_add_signed(dy))
+        };
+
+        proposed.clear();
+        for (elf, &pos) in elves.positions.iter().enumerate() {
             // check adjacent positions
             let mut n = 0;
-            for y in pos.1 - 1..=pos.1 + 1 {
-                for x in pos.0 - 1..=pos.0 + 1 {
-                    let adj = (x, y);
-                    if adj != *pos && positions.contains(&adj) {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if (dx, dy) != (0, 0) && occupied(pos, dx, dy) {
                         n += 1;
                     }
                 }
@@ -71,13 +76,13 @@ fn simulate(positions: &mut PointSet, max_rounds: usize) -> usize {
                 let dir = DIRECTIONS[(start_dir + d) % 4];
                 let empty = (-1..=1)
                     .map(|a| if dir.0 == 0 { (a, dir.1) } else { (dir.0, a) })
-                    .all(|p| !positions.contains(&(pos.0 + p.0, pos.1 + p.1)));
+                    .all(|p| !occupied(pos, p.0, p.1));
                 if empty {
-                    let new_pos = (pos.0 + dir.0, pos.1 + dir.1);
+                    let new_pos = (pos.0.wrapping_add_signed(dir.0), pos.1.wrapping_add_signed(dir.1));
                     proposed
                         .entry(new_pos)
-                        .and_modify(|e| *e = (isize::MIN, isize::MIN))
-                        .or_insert(*pos);
+                        .and_modify(|e| *e = None)
+                        .or_insert(Some(elf));
                     break;
                 }
             }
@@ -89,21 +94,77 @@ fn simulate(positions: &mut PointSet, max_rounds: usize) -> usize {
         }
 
         // move to proposed position if uniquely selected
-        for (new_pos, old_pos) in &proposed {
-            if positions.remove(old_pos) {
-                positions.insert(*new_pos);
+        for (&new_pos, &elf) in &proposed {
+            if let Some(elf) = elf {
+                let old_pos = std::mem::replace(&mut elves.positions[elf], new_pos);
+                elves.grid.remove(old_pos.0, old_pos.1);
+                elves.grid.insert(new_pos.0, new_pos.1);
             }
         }
 
         start_dir = (start_dir + 1) % 4;
+        visual::emit(|| render(elves).with_caption(format!("round {}", round + 1)));
     }
 
     usize::MAX // did not terminate
 }
 
-type Point = (isize, isize);
-type PointSet = HashSet<Point>;
-type PointMap = HashMap<Point, Point>;
+fn render(elves: &Elves) -> Frame {
+    let (min, max) = elves.bounds();
+    let mut frame = Frame::new(max.0 + 1 - min.0, max.1 + 1 - min.1);
+    for p in &elves.positions {
+        frame.set(p.0 - min.0, p.1 - min.1, b'#');
+    }
+    frame
+}
+
+/// Elf positions, mirrored in a grid with an empty border to spread into
+struct Elves {
+    /// grid position of the input's top left corner
+    origin: usize,
+    positions: Vec<Point>,
+    grid: BitGrid,
+}
+
+impl Elves {
+    /// Moves everything into a bigger grid if an elf could leave it this round
+    fn ensure_border(&mut self) {
+        const MARGIN: usize = 32;
+        let (w, h) = (self.grid.width(), self.grid.height());
+        if self.positions.iter().all(|&(x, y)| x >= 2 && y >= 2 && x + 2 < w && y + 2 < h) {
+            return;
+        }
+        self.origin += MARGIN;
+        self.grid = BitGrid::new(w + 2 * MARGIN, h + 2 * MARGIN);
+        for pos in &mut self.positions {
+            *pos = (pos.0 + MARGIN, pos.1 + MARGIN);
+            self.grid.insert(pos.0, pos.1);
+        }
+    }
+
+    /// top left and bottom right corner of occupied area
+    fn bounds(&self) -> (Point, Point) {
+        self.positions.iter().fold(
+            ((usize::MAX, usize::MAX), (usize::MIN, usize::MIN)),
+            |acc, p| {
+                (
+                    (acc.0 .0.min(p.0), acc.0 .1.min(p.1)),
+                    (acc.1 .0.max(p.0), acc.1 .1.max(p.1)),
+                )
+            },
+        )
+    }
+
+    /// positions relative to the input's top left corner
+    #[cfg(test)]
+    fn points(&self) -> std::collections::HashSet<(isize, isize)> {
+        let origin = self.origin as isize;
+        self.positions.iter().map(|&(x, y)| (x as isize - origin, y as isize - origin)).collect()
+    }
+}
+
+type Point = (usize, usize);
+type PointMap = FastHashMap<Point, Option<usize>>;
 
 #[test]
 pub fn test() {
@@ -115,13 +176,13 @@ pub fn test() {
     let test_input2 = vec!["..##.", ".....", "..#..", "...#.", "..#..", "....."];
     let test_input3 = vec![".....", "..##.", ".#...", "....#", ".....", "..#.."];
 
-    let mut points = parse_input(&test_input1);
-    simulate(&mut points, 1);
-    assert_eq!(points, parse_input(&test_input2));
+    let mut elves = parse_input(&test_input1);
+    simulate(&mut elves, 1);
+    assert_eq!(elves.points(), parse_input(&test_input2).points());
 
-    let mut points = parse_input(&test_input1);
-    simulate(&mut points, 2);
-    assert_eq!(points, parse_input(&test_input3));
+    let mut elves = parse_input(&test_input1);
+    simulate(&mut elves, 2);
+    assert_eq!(elves.points(), parse_input(&test_input3).points());
 
     assert_eq!(a(&test_input1), "25");
     assert_eq!(a(&input), "110");
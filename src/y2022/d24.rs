@@ -2,7 +2,9 @@
 //!
 //! Assumptions: Map is rectangular, entrance at top left, exit at bottom right
 
-use std::{collections::{HashSet, BinaryHeap}, cmp::Reverse};
+use std::{collections::{HashMap, HashSet, BinaryHeap}, cmp::Reverse};
+
+use crate::visual::{self, Frame};
 
 /// Fewest number of steps required to reach goal
 pub fn a(input: &Vec<&str>) -> String {
@@ -65,6 +67,8 @@ fn parse_input(input: &Vec<&str>) -> Map {
 fn find_path(map: &Map, start: &Point, end: &Point, step: usize) -> usize {
     let mut visited = HashSet::from([(*start, step)]);
     let mut queue = BinaryHeap::from([(Reverse(0), *start, step)]);
+    // predecessors are only tracked for replaying the path
+    let mut parents = visual::enabled().then(HashMap::new);
 
     while let Some((_, pos, step)) = queue.pop() {
         for v in [(1, 0), (0, 1), (-1, 0), (0, -1), (0, 0)] {
@@ -72,6 +76,9 @@ fn find_path(map: &Map, start: &Point, end: &Point, step: usize) -> usize {
 
             // check target (on boundary)
             if new_pos == *end {
+                if let Some(parents) = &parents {
+                    replay_path(map, parents, (pos, step), *end);
+                }
                 return step + 1;
             }
 
@@ -93,6 +100,9 @@ fn find_path(map: &Map, start: &Point, end: &Point, step: usize) -> usize {
             }
 
             if visited.insert((new_pos, step + 1)) {
+                if let Some(parents) = parents.as_mut() {
+                    parents.insert((new_pos, step + 1), pos);
+                }
                 queue.push((Reverse(step + distance(&new_pos, end)), new_pos, step + 1));
             }
         }
@@ -101,6 +111,18 @@ fn find_path(map: &Map, start: &Point, end: &Point, step: usize) -> usize {
     unreachable!("failed to find solution");
 }
 
+/// emits frames of the expedition moving along the found path
+fn replay_path(map: &Map, parents: &HashMap<(Point, usize), Point>, last: (Point, usize), end: Point) {
+    let mut path = vec![(end, last.1 + 1), last];
+    while let Some(&pos) = parents.get(path.last().unwrap()) {
+        let step = path.last().unwrap().1 - 1;
+        path.push((pos, step));
+    }
+    for &(pos, step) in path.iter().rev() {
+        visual::emit(|| map.render(step, &pos).with_caption(format!("minute {step}")));
+    }
+}
+
 /// calculates Manhattan Distance between two points
 fn distance(p1: &Point, p2: &Point) -> usize {
     (p1.0.abs_diff(p2.0) + p1.1.abs_diff(p2.1)) as usize
@@ -179,6 +201,39 @@ impl Map {
         map
     }
 
+    fn render(&self, step: usize, expedition: &Point) -> Frame {
+        let mut frame = Frame::new(self.width as usize, self.height as usize);
+        for x in 0..self.width as usize {
+            frame.set(x, 0, b'#');
+            frame.set(x, self.height as usize - 1, b'#');
+        }
+        for y in 0..self.height as usize {
+            frame.set(0, y, b'#');
+            frame.set(self.width as usize - 1, y, b'#');
+        }
+        frame.set(1, 0, b'.');
+        frame.set(self.width as usize - 2, self.height as usize - 1, b'.');
+
+        let mut counts = HashMap::<Point, u8>::new();
+        for b in &self.blizzards_per_step[step % self.periodicity] {
+            *counts.entry(b.pos).or_default() += 1;
+            let cell = match counts[&b.pos] {
+                1 => match b.dir {
+                    Direction::East => b'>',
+                    Direction::South => b'v',
+                    Direction::West => b'<',
+                    Direction::North => b'^',
+                },
+                n => b'0' + n.min(9),
+            };
+            frame.set(b.pos.0 as usize, b.pos.1 as usize, cell);
+        }
+        // the entrance is modelled as being left of the first field
+        let expedition = if *expedition == (0, 1) { (1, 0) } else { *expedition };
+        frame.set(expedition.0 as usize, expedition.1 as usize, b'E');
+        frame
+    }
+
     fn has_blizzard(&self, step: &usize, pos: &Point) -> bool {
         self.blizzards_per_step[*step % self.periodicity]
             .binary_search_by_key(&pos, |b| &b.pos)
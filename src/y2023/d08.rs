@@ -1,6 +1,6 @@
 //! Day 8: Haunted Wasteland
 
-use crate::utils;
+use crate::utils::num;
 use std::collections::HashMap;
 
 /// steps required to reach goal
@@ -12,15 +12,14 @@ pub fn a(input: &Vec<&str>) -> String {
 /// simultaneous steps required to reach goals
 pub fn b(input: &Vec<&str>) -> String {
     let graph = parse_graph(input);
-    let steps = graph
+    let cycles = graph
         .keys()
-        .filter_map(|node| {
-            node.ends_with('A')
-                .then(|| count_steps(node, &input[0], &graph, |n| n.ends_with('Z')))
-        })
+        .filter(|node| node.ends_with('A'))
+        .map(|node| find_cycle(node, input[0], &graph, |n| n.ends_with('Z')))
         .collect::<Vec<_>>();
-    // println!("steps: {:?}", steps);
-    utils::lcm(&steps).to_string()
+    first_common_goal(&cycles)
+        .expect("goals are never reached simultaneously")
+        .to_string()
 }
 
 fn parse_graph<'a>(input: &'a Vec<&'a str>) -> Graph<'a> {
@@ -46,6 +45,73 @@ fn count_steps(start: &str, directions: &str, graph: &Graph, condition: fn(&str)
     steps
 }
 
+/// Walk that eventually repeats, as position and direction have finitely many states
+struct Cycle {
+    /// step at which the repetition starts
+    start: usize,
+    period: usize,
+    /// steps reaching a goal before the end of the first period
+    goals: Vec<usize>,
+}
+
+impl Cycle {
+    fn reaches_goal(&self, step: usize) -> bool {
+        let step = if step < self.start { step } else { self.start + (step - self.start) % self.period };
+        self.goals.contains(&step)
+    }
+}
+
+fn find_cycle<'a>(start: &'a str, directions: &str, graph: &Graph<'a>, condition: fn(&str) -> bool) -> Cycle {
+    let mut seen = HashMap::new();
+    let mut goals = Vec::new();
+    let mut pos = start;
+    for step in 0.. {
+        let i = step % directions.len();
+        if let Some(&first) = seen.get(&(pos, i)) {
+            return Cycle { start: first, period: step - first, goals };
+        }
+        seen.insert((pos, i), step);
+        if condition(pos) {
+            goals.push(step);
+        }
+        pos = match directions.as_bytes()[i] {
+            b'L' => graph[pos].0,
+            b'R' => graph[pos].1,
+            _ => unreachable!(),
+        };
+    }
+    unreachable!()
+}
+
+/// first step at which all walks reach a goal
+fn first_common_goal(cycles: &[Cycle]) -> Option<usize> {
+    let cycling = cycles.iter().map(|cycle| cycle.start).max()?;
+    if let Some(step) = (0..cycling).find(|&step| cycles.iter().all(|cycle| cycle.reaches_goal(step))) {
+        return Some(step);
+    }
+
+    // afterwards goals are periodic, combine those of each cycle
+    let mut congruences = vec![(0, 1)];
+    for cycle in cycles {
+        congruences = congruences
+            .iter()
+            .flat_map(|&congruence| {
+                cycle.goals.iter().filter(|&&goal| goal >= cycle.start).filter_map(move |&goal| {
+                    let period = cycle.period as u64;
+                    num::crt(&[congruence, (goal as u64 % period, period)])
+                })
+            })
+            .collect();
+    }
+    congruences
+        .iter()
+        .map(|&(residue, modulus)| {
+            let (residue, modulus) = (residue as usize, modulus as usize);
+            residue + cycling.saturating_sub(residue).div_ceil(modulus) * modulus
+        })
+        .min()
+}
+
 type Graph<'a> = HashMap<&'a str, (&'a str, &'a str)>;
 
 #[test]
@@ -86,4 +152,19 @@ pub fn test() {
     assert_eq!(a(&input), "2");
     assert_eq!(a(&input2), "6");
     assert_eq!(b(&input3), "6");
+
+    // goals are reached at even steps from 2 and every third step from 1
+    let input4 = vec![
+        "L",
+        "",
+        "11A = (11B, XXX)",
+        "11B = (11Z, XXX)",
+        "11Z = (11B, XXX)",
+        "22A = (22Z, XXX)",
+        "22Z = (22B, XXX)",
+        "22B = (22C, XXX)",
+        "22C = (22Z, XXX)",
+        "XXX = (XXX, XXX)",
+    ];
+    assert_eq!(b(&input4), "4");
 }
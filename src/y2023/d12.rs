@@ -1,13 +1,12 @@
 //! Day 12: Hot Springs
 
-use std::collections::HashMap;
+use crate::utils::{memo::Memo, parallel::par_map};
 
 /// sum of operational/broken spring arrangement permutations
 pub fn a(input: &Vec<&str>) -> String {
     let records = parse_input(input);
-    records
+    par_map(&records, |(conditions, sizes)| find_combinations(conditions, sizes))
         .iter()
-        .map(|(conditions, sizes)| find_combinations(conditions, sizes))
         .sum::<usize>()
         .to_string()
 }
@@ -15,15 +14,13 @@ pub fn a(input: &Vec<&str>) -> String {
 /// sum of permutations with repetitions
 pub fn b(input: &Vec<&str>) -> String {
     let records = parse_input(input);
-    records
-        .iter()
-        .map(|record| {
-            let (conditions, sizes) = unfold(record);
-            find_combinations(&conditions, &sizes)
-        })
-        // .inspect(|n| println!("{}", n))
-        .sum::<usize>()
-        .to_string()
+    par_map(&records, |record| {
+        let (conditions, sizes) = unfold(record);
+        find_combinations(&conditions, &sizes)
+    })
+    .iter()
+    .sum::<usize>()
+    .to_string()
 }
 
 fn parse_input<'a>(input: &'a Vec<&'a str>) -> Records<'a> {
@@ -50,56 +47,41 @@ fn unfold<'a>(record: &'a Record<'a>) -> (String, Vec<u8>) {
 }
 
 fn find_combinations(conditions: &str, sizes: &[u8]) -> usize {
-    fn combinations(conditions: &str, sizes: &[u8], matching: u8, cache: &mut Cache) -> usize {
-        if let Some(val) = cache.get(&(conditions.len(), sizes.len(), matching)) {
-            return *val; // cache hit
-        }
-
-        let result = match conditions.bytes().next() {
-            Some(b'#') => combinations(&conditions[1..], sizes, matching + 1, cache),
-            Some(b'.') => {
-                if !sizes.is_empty() && sizes[0] == matching {
-                    combinations(&conditions[1..], &sizes[1..], 0, cache)
-                } else if matching == 0 {
-                    combinations(&conditions[1..], sizes, 0, cache)
-                } else {
-                    0
-                }
+    let conditions = conditions.as_bytes();
+    // (next condition, next size, damaged springs in current group)
+    Memo::new().get((0, 0, 0), &|combinations, (i, j, matching): (usize, usize, u8)| {
+        // current group ends at an operational spring
+        let mut operational = || {
+            if j < sizes.len() && sizes[j] == matching {
+                combinations((i + 1, j + 1, 0))
+            } else if matching == 0 {
+                combinations((i + 1, j, 0))
+            } else {
+                0
             }
+        };
+
+        match conditions.get(i) {
+            Some(b'.') => operational(),
+            Some(b'#') => combinations((i + 1, j, matching + 1)),
             Some(b'?') => {
-                // consider both damaged ('#') and operational ('.') options
-                let damaged = combinations(&conditions[1..], sizes, matching + 1, cache);
-                let operational = if !sizes.is_empty() && sizes[0] == matching {
-                    combinations(&conditions[1..], &sizes[1..], 0, cache)
-                } else if matching == 0 {
-                    combinations(&conditions[1..], sizes, 0, cache)
-                } else {
-                    0
-                };
-                damaged + operational
+                // consider both operational ('.') and damaged ('#') options
+                operational() + combinations((i + 1, j, matching + 1))
             }
             Some(_) => unreachable!("invalid character"),
             None => {
-                if sizes.is_empty() && matching == 0 {
+                if j == sizes.len() && matching == 0 {
                     1
-                } else if sizes.len() == 1 && sizes[0] == matching {
+                } else if j + 1 == sizes.len() && sizes[j] == matching {
                     1
                 } else {
                     0
                 }
             }
-        };
-
-        cache.insert((conditions.len(), sizes.len(), matching), result);
-
-        result
-    }
-
-    let mut cache = Cache::new();
-    combinations(conditions, sizes, 0, &mut cache)
+        }
+    })
 }
 
-type Cache = HashMap<(usize, usize, u8), usize>;
 type Record<'a> = (&'a str, Vec<u8>);
 type Records<'a> = Vec<Record<'a>>;
 
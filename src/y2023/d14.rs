@@ -2,6 +2,8 @@
 
 use std::collections::HashMap;
 
+use crate::visual::{self, Frame};
+
 /// total load after tilting north
 pub fn a(input: &Vec<&str>) -> String {
     let mut height = vec![0; input[0].len()];
@@ -33,6 +35,8 @@ pub fn b(input: &Vec<&str>) -> String {
             turn_clockwise(&mut grid);
             tilt_east(&mut grid);
         }
+        visual::emit(|| Frame::from_rows(grid.iter().map(|row| row.iter().copied()))
+            .with_caption(format!("cycle {}", i + 1)));
         if let Some(cycle) = cache.get(&grid) {
             let length = i - cycle;
             // println!(
@@ -1,5 +1,7 @@
 //! Day 16: The Floor Will Be Lava
 
+use crate::visual::{self, Frame};
+
 /// number of visited tiles
 pub fn a(input: &Vec<&str>) -> String {
     let grid = parse_input(input);
@@ -96,9 +98,23 @@ fn trace(mut x: usize, mut y: usize, mut dir: u8, grid: &Grid, visited: &mut Vec
 fn count_energized(x: usize, y: usize, dir: u8, grid: &Grid) -> usize {
     let mut visited = vec![0u8; grid.tiles.len()];
     trace(x, y, dir, &grid, &mut visited);
+    visual::emit(|| render(grid, &visited).with_caption(format!("entering at {x},{y}")));
     visited.iter().filter(|&&v| v > 0).count()
 }
 
+/// shows mirrors and splitters on energized tiles as walls
+fn render(grid: &Grid, visited: &[u8]) -> Frame {
+    Frame::from_rows(grid.tiles.chunks(grid.width).zip(visited.chunks(grid.width)).map(
+        |(tiles, visited)| {
+            tiles.iter().zip(visited).map(|(&tile, &v)| match (tile, v) {
+                (b'.', 0) => b' ',
+                (b'.', _) => b'#',
+                _ => tile,
+            })
+        },
+    ))
+}
+
 const RIGHT: u8 = 1;
 const DOWN: u8 = 2;
 const LEFT: u8 = 4;
@@ -2,6 +2,8 @@
 
 use std::collections::HashMap;
 
+use crate::utils::interval::Interval;
+
 /// sum of accepted part ratings
 pub fn a(input: &Vec<&str>) -> String {
     let (workflows, parts) = parse_input(input);
@@ -14,7 +16,7 @@ pub fn a(input: &Vec<&str>) -> String {
 
 /// number of accepted part ratings combinations
 pub fn b(input: &Vec<&str>) -> String {
-    const RANGE: Range = (1, 4000);
+    const RANGE: Interval<u16> = Interval { start: 1, end: 4001 };
     let (workflows, _) = parse_input(input);
     combinations("in", &[RANGE, RANGE, RANGE, RANGE], &workflows).to_string()
 }
@@ -100,17 +102,17 @@ fn classify_part(part: &Part, workflows: &Workflows) -> bool {
     unreachable!()
 }
 
-fn combinations(name: &str, ranges: &[Range; 4], workflows: &Workflows) -> u64 {
+fn combinations(name: &str, ranges: &[Interval<u16>; 4], workflows: &Workflows) -> u64 {
     if name == "A" {
         return ranges
             .iter()
-            .fold(1, |acc, (min, max)| acc * (max + 1 - min) as u64);
+            .fold(1, |acc, range| acc * range.len() as u64);
     } else if name == "R" {
         return 0;
     }
 
     let rules = &workflows[name];
-    let mut ranges = ranges.clone();
+    let mut ranges = *ranges;
     rules
         .iter()
         .map(|rule| match rule {
@@ -120,14 +122,17 @@ fn combinations(name: &str, ranges: &[Range; 4], workflows: &Workflows) -> u64 {
                 value,
                 destination,
             } => {
-                let mut new_ranges = ranges.clone();
-                if *less {
-                    new_ranges[*category as usize].1 = value - 1; // set max
-                    ranges[*category as usize].0 = *value; // set min
+                let mut new_ranges = ranges;
+                let range = ranges[*category as usize];
+                // matching part goes to destination, the rest to the next rule
+                let (matching, rest) = if *less {
+                    range.split_at(*value)
                 } else {
-                    new_ranges[*category as usize].0 = value + 1; // set min
-                    ranges[*category as usize].1 = *value; // set max
-                }
+                    let (below, above) = range.split_at(value + 1);
+                    (above, below)
+                };
+                new_ranges[*category as usize] = matching;
+                ranges[*category as usize] = rest;
                 combinations(destination, &new_ranges, workflows)
             }
             Rule::Fallback(destination) => combinations(destination, &ranges, workflows),
@@ -146,7 +151,6 @@ enum Rule<'a> {
 }
 type Workflows<'a> = HashMap<&'a str, Vec<Rule<'a>>>;
 type Part = [u16; 4];
-type Range = (u16, u16);
 
 #[test]
 pub fn test() {
@@ -1,6 +1,6 @@
 //! Day 20: Pulse Propagation
 
-use crate::utils;
+use crate::{dot, utils::num};
 use std::collections::{HashMap, VecDeque};
 
 /// product of low and high pulse count
@@ -37,34 +37,51 @@ pub fn b(input: &Vec<&str>) -> String {
     // in 'rx'. There are a few distinct subgraphs starting right after
     // 'broadcaster', which have their own periodicity.
     let final_module_idx = cables[final_cable_idx].0;
-    let subgraph_count = modules[final_module_idx].cables_in.len();
-    let mut periods = Vec::<usize>::with_capacity(subgraph_count);
+    let inputs = &modules[final_module_idx].cables_in;
+    // first two presses with a high pulse on each input
+    let mut presses = vec![Vec::<usize>::with_capacity(2); inputs.len()];
 
     let mut iterations = 0;
-    while cable_states[final_cable_idx] && iterations < 10000 {
+    while cable_states[final_cable_idx] && iterations < MAX_PRESSES {
         iterations += 1;
         propagate(
             &modules,
             &cables,
             &mut flip_flop_states,
             &mut cable_states,
-            |idx| {
-                if idx == final_module_idx {
-                    periods.push(iterations);
+            |cable_idx| {
+                if let Some(i) = inputs.iter().position(|&c| c == cable_idx)
+                    && presses[i].len() < 2
+                {
+                    presses[i].push(iterations);
                 }
             },
         );
-        if periods.len() == subgraph_count {
+        if presses.iter().all(|p| p.len() == 2) {
             break;
         }
     }
 
-    if !periods.is_empty() {
-        // println!("Periods: {periods:?}");
-        iterations = utils::lcm(&periods);
+    if !cable_states[final_cable_idx] {
+        return iterations.to_string();
     }
+    assert!(
+        presses.iter().all(|p| p.len() == 2),
+        "no period of all inputs within {MAX_PRESSES} presses: {presses:?}"
+    );
 
-    iterations.to_string()
+    // periods may start at an offset
+    let congruences = presses
+        .iter()
+        .map(|p| {
+            let period = (p[1] - p[0]) as u64;
+            (p[0] as u64 % period, period)
+        })
+        .collect::<Vec<_>>();
+    let (press, period) = num::crt(&congruences).expect("inputs are never high together");
+    let (press, period) = (press as usize, period as usize);
+    let first = presses.iter().map(|p| p[0]).max().unwrap();
+    (press + first.saturating_sub(press).div_ceil(period) * period).to_string()
 }
 
 fn parse_input(input: &Vec<&str>) -> (Modules, Cables) {
@@ -115,6 +132,23 @@ fn parse_input(input: &Vec<&str>) -> (Modules, Cables) {
         }
     }
 
+    dot::export(|| {
+        let mut names = module_map.iter().collect::<Vec<_>>();
+        names.sort_unstable_by_key(|(_, (idx, _))| *idx);
+        let mut graph = dot::Graph::directed("modules");
+        for (name, (idx, adj)) in names {
+            graph.node(name).kind(match modules[*idx].mtype {
+                ModuleType::FlipFlop => "flip-flop",
+                ModuleType::Conjunction => "conjunction",
+                ModuleType::Broadcast => "broadcast",
+            });
+            for other in adj.split(", ").filter(|s| !s.is_empty()) {
+                graph.edge(name, other);
+            }
+        }
+        graph
+    });
+
     (modules, cables)
 }
 
@@ -156,7 +190,7 @@ fn propagate(
             }
             ModuleType::Conjunction => {
                 if pulse {
-                    handle_conj_high_pulse(receiver_idx);
+                    handle_conj_high_pulse(cable_idx);
                 }
 
                 // low only if all incoming high
@@ -177,6 +211,11 @@ fn propagate(
     (low_count, high_count)
 }
 
+/// Presses to find the periods of the penultimate module's inputs in. The inputs
+/// seen so far are each driven by a counter of 12 flip-flops with a period below
+/// 2^12, so their second high pulse comes within two periods.
+const MAX_PRESSES: usize = 2 << 12;
+
 #[derive(PartialEq)]
 enum ModuleType {
     FlipFlop,
@@ -2,6 +2,8 @@
 
 use std::collections::HashSet;
 
+use crate::utils::{cuboid::Cuboid, interval::Interval};
+
 /// number of bricks not solely supporting any others
 pub fn a(input: &Vec<&str>) -> String {
     let bricks = parse_input(input);
@@ -49,69 +51,53 @@ fn parse_input(input: &Vec<&str>) -> Bricks {
         .iter()
         .map(|s| {
             s.split_once('~')
-                .map(|(s1, s2)| (parse_point(s1), parse_point(s2)))
+                .map(|(s1, s2)| Cuboid::inclusive(parse_point(s1), parse_point(s2)))
                 .unwrap()
         })
         .collect();
-    bricks.sort_unstable_by_key(|b| b.0.z);
+    bricks.sort_unstable_by_key(|b| b.ranges[2].start);
     bricks
 }
 
-fn parse_point(s: &str) -> Point {
-    Point {
-        x: s[0..1].parse().unwrap(),
-        y: s[2..3].parse().unwrap(),
-        z: s[4..].parse().unwrap(),
-    }
+fn parse_point(s: &str) -> [i64; 3] {
+    let mut it = s.split(',').map(|n| n.parse().unwrap());
+    [it.next().unwrap(), it.next().unwrap(), it.next().unwrap()]
 }
 
 fn simulate(bricks: &Bricks) -> (Vec<Support>, Vec<Support>) {
-    const DIM: u8 = 10;
-    let mut heightmap = [(0u16, u16::MAX); (DIM * DIM) as usize];
+    let mut settled = Bricks::with_capacity(bricks.len());
     let mut supports = vec![Support::new(); bricks.len()];
     let mut supported_by = vec![Support::new(); bricks.len()];
 
     for (idx, brick) in bricks.iter().enumerate() {
-        assert!(brick.0.x <= brick.1.x);
-        assert!(brick.0.y <= brick.1.y);
-        assert!(brick.0.z <= brick.1.z);
-
-        // first find highest 'peaks' under the brick
-        let mut max_z = 0;
-        for x in brick.0.x..=brick.1.x {
-            for y in brick.0.y..=brick.1.y {
-                let z = heightmap[(x + y * DIM) as usize].0;
-                if z > max_z {
-                    max_z = z;
-                }
-            }
-        }
+        assert!(!brick.is_empty());
 
-        // then add the brick on top and update support links
-        let height = brick.1.z + 1 - brick.0.z;
-        for x in brick.0.x..=brick.1.x {
-            for y in brick.0.y..=brick.1.y {
-                let (z, prev_idx) = &mut heightmap[(x + y * DIM) as usize];
-                if *z == max_z && *prev_idx != u16::MAX {
-                    supported_by[idx].insert(*prev_idx);
-                    supports[*prev_idx as usize].insert(idx as u16);
-                }
-                *z = max_z + height;
-                *prev_idx = idx as u16;
+        // first find highest bricks in the column under the brick
+        let [x, y, z] = brick.ranges;
+        let column = Cuboid::new([x, y, Interval::new(1, z.start)]);
+        let floor = settled
+            .iter()
+            .filter(|other| other.intersects(&column))
+            .map(|other| other.ranges[2].end)
+            .max()
+            .unwrap_or(1);
+
+        // then drop the brick on top and update support links
+        let brick = brick.translate([0, 0, floor - z.start]);
+        let below = brick.translate([0, 0, -1]);
+        for (other_idx, other) in settled.iter().enumerate() {
+            if other.intersects(&below) {
+                supported_by[idx].insert(other_idx as u16);
+                supports[other_idx].insert(idx as u16);
             }
         }
+        settled.push(brick);
     }
 
     (supports, supported_by)
 }
 
-struct Point {
-    x: u8,
-    y: u8,
-    z: u16,
-}
-type Brick = (Point, Point);
-type Bricks = Vec<Brick>;
+type Bricks = Vec<Cuboid<3>>;
 type Support = HashSet<u16>;
 
 #[test]
@@ -1,5 +1,7 @@
 //! Day 24: Never Tell Me The Odds
 
+use crate::utils::{self, linalg::{self, Rational}};
+
 const MIN: f64 = 200000000000000.0;
 const MAX: f64 = 400000000000000.0;
 
@@ -11,26 +13,44 @@ pub fn a(input: &Vec<&str>) -> String {
 
 /// sum of initial position coords of line intersecting all input lines
 pub fn b(input: &Vec<&str>) -> String {
-    let lines = parse_input(input);
+    let hailstones = parse_input(input)
+        .iter()
+        .map(|(p, v)| ([p.x, p.y, p.z].map(|c| c as i128), [v.x, v.y, v.z].map(|c| c as i128)))
+        .collect::<Vec<_>>();
 
-    let solutions = [(0, 1, 3, 4), (2, 1, 5, 4)].map(|(x, y, dx, dy)| {
-        let matrix = lines
-            .iter()
-            .map(|line| [line.0.x, line.0.y, line.0.z, line.1.x, line.1.y, line.1.z])
-            .map(|v| vec![-v[dy], v[dx], v[y], -v[x], v[y] * v[dx] - v[x] * v[dy]])
-            .collect::<Matrix>();
-        let last = matrix.last().unwrap();
-        let mut matrix = matrix
-            .iter()
-            .take(4)
-            .map(|row| row.iter().zip(last.iter()).map(|(a, b)| a - b).collect())
-            .collect::<Matrix>();
-        gaussian_elimination(&mut matrix)
+    // in the frame of the first hailstone, the rock passes through the origin and
+    // lies in each plane spanned by the origin and another hailstone's path
+    let (origin, drift) = hailstones[0];
+    let relative = hailstones[1..]
+        .iter()
+        .map(|&(p, v)| (sub(p, origin), sub(v, drift)))
+        .collect::<Vec<_>>();
+    let normals = relative.iter().map(|&(p, v)| cross(p, v)).collect::<Vec<_>>();
+    // all planes intersect in a single line
+    let rank = linalg::rank(&normals.iter().map(|n| n.to_vec()).collect::<Vec<_>>());
+    assert_eq!(rank, Ok(2), "hailstone paths don't determine one rock trajectory");
+    let direction = normals[1..]
+        .iter()
+        .map(|&normal| cross(normals[0], normal))
+        .find(|&direction| direction != [0; 3])
+        .expect("rock trajectory is not determined");
+    let divisor = direction.iter().fold(0, |d, &c| utils::gcd(d, c.abs()));
+    let direction = direction.map(|c| c / divisor);
+
+    // time and place of collision with the next two hailstones
+    let collisions = relative[..2].iter().zip(&hailstones[1..]).map(|(&(p, v), &(position, velocity))| {
+        let matrix = (0..3).map(|k| vec![v[k], -direction[k], -p[k]]).collect::<Vec<_>>();
+        let time = linalg::solve(&matrix).expect("hailstone moves along the rock's path")[0];
+        let place = [0, 1, 2].map(|k| Rational::from(position[k]) + Rational::from(velocity[k]) * time);
+        (time, place)
     });
+    let [(t1, p1), (t2, p2)] = collisions.collect::<Vec<_>>().try_into().unwrap();
 
-    // println!("{solutions:?}");
-
-    ((solutions[0][0] + solutions[0][1] + solutions[1][0]) as i64).to_string()
+    let sum = (0..3)
+        .map(|k| p1[k] - (p2[k] - p1[k]) / (t2 - t1) * t1)
+        .fold(Rational::from(0), |sum, c| sum + c);
+    assert_eq!(sum.den, 1, "position {sum} is not an integer");
+    sum.to_string()
 }
 
 fn parse_input(input: &Vec<&str>) -> Lines {
@@ -94,28 +114,16 @@ fn count_intersections_2d(lines: &Lines, min: f64, max: f64) -> usize {
     intersections
 }
 
-fn gaussian_elimination(matrix: &mut Matrix) -> Vec<f64> {
-    for i in 0..matrix.len() {
-        let t = matrix[i][i];
-        matrix[i].iter_mut().for_each(|x| *x /= t);
-        for j in (i + 1)..matrix.len() {
-            let t = matrix[j][i];
-            for k in 0..matrix[i].len() {
-                matrix[j][k] -= t * matrix[i][k];
-            }
-        }
-    }
-
-    for i in (0..matrix.len()).rev() {
-        for j in 0..i {
-            let t = matrix[j][i];
-            for k in 0..matrix[i].len() {
-                matrix[j][k] -= t * matrix[i][k];
-            }
-        }
-    }
+fn sub(a: Vector, b: Vector) -> Vector {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
 
-    matrix.iter().map(|v| *v.last().unwrap()).collect()
+fn cross(a: Vector, b: Vector) -> Vector {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
 }
 
 struct Point {
@@ -125,7 +133,7 @@ struct Point {
 }
 type Line = (Point, Point);
 type Lines = Vec<Line>;
-type Matrix = Vec<Vec<f64>>;
+type Vector = [i128; 3];
 
 #[test]
 pub fn test() {
@@ -151,5 +159,5 @@ pub fn test() {
     assert_eq!(count_intersections_2d(&lines, 7.0, 27.0), 2);
 
     // assert_eq!(a(&input), "2");
-    // assert_eq!(b(&input), "47");
+    assert_eq!(b(&input), "47");
 }
@@ -1,6 +1,7 @@
 //! Day 5: Print Queue
 
-use std::collections::{HashMap, HashSet};
+use crate::utils::graph::Graph;
+use std::collections::HashSet;
 
 /// sum of middle page numbers in valid sequences
 pub fn a(input: &Vec<&str>) -> String {
@@ -50,54 +51,25 @@ fn check_sequence(seq: &Sequence, rules: &Rules) -> bool {
 
 /// finds the mid page in a correctly ordered page sequence
 fn find_correct_mid(seq: &Sequence, rules: &Rules) -> usize {
-    let mut count = 0;
-
     // build a page graph, ignore irrelevant rules
-    let mut graph =
-        HashMap::<_, _>::from_iter(seq.iter().map(|page| (*page, GraphNode::default())));
-    for (r1, r2) in rules {
-        if !graph.contains_key(r1) || !graph.contains_key(r2) {
-            continue;
-        }
-        graph.get_mut(r1).unwrap().after.push(r2);
-        graph.get_mut(r2).unwrap().before.insert(r1);
+    let mut graph = Graph::directed();
+    for page in seq {
+        graph.node(*page);
     }
-
-    // list of pages with none before
-    let mut stack = graph
-        .iter()
-        .filter_map(|(page, node)| node.before.is_empty().then_some(*page))
-        .collect::<Vec<_>>();
-
-    // do a top sort, but just count until we get to the mid page
-    while let Some(page) = stack.pop() {
-        count += 1;
-        if count > seq.len() / 2 {
-            // found the mid
-            return page.parse().unwrap();
-        }
-        while let Some(a) = graph.get_mut(page).unwrap().after.pop() {
-            let after_node = graph.get_mut(&a).unwrap();
-            after_node.before.remove(page);
-            if after_node.before.is_empty() {
-                stack.push(&a);
-            }
+    for &(r1, r2) in rules {
+        if graph.index(r1).is_some() && graph.index(r2).is_some() {
+            graph.edge(r1, r2);
         }
     }
 
-    unreachable!()
+    let order = graph.topological_sort().expect("cyclic page rules");
+    graph.label(order[seq.len() / 2]).parse().unwrap()
 }
 
 type Rules<'a> = Vec<(&'a str, &'a str)>;
 type Sequence<'a> = Vec<&'a str>;
 type Sequences<'a> = Vec<Sequence<'a>>;
 
-#[derive(Default)]
-struct GraphNode<'a> {
-    before: HashSet<&'a str>,
-    after: Vec<&'a str>,
-}
-
 #[test]
 pub fn test() {
     let input = vec![
@@ -1,23 +1,27 @@
 //! Day 7: Bridge Repair
 
+use crate::utils::parallel::par_map;
+
 /// sum of results of solvable equations with operators + and *
 pub fn a(input: &Vec<&str>) -> String {
     let equations = parse_input(input);
-    equations
-        .iter()
-        .filter_map(|(value, numbers)| find_operators(value, 0, &numbers, false).then_some(value))
-        .sum::<usize>()
-        .to_string()
+    par_map(&equations, |(value, numbers)| {
+        if find_operators(value, 0, numbers, false) { *value } else { 0 }
+    })
+    .iter()
+    .sum::<usize>()
+    .to_string()
 }
 
 /// sum of results of solvable equations with operators +, * and concat
 pub fn b(input: &Vec<&str>) -> String {
     let equations = parse_input(input);
-    equations
-        .iter()
-        .filter_map(|(value, numbers)| find_operators(value, 0, &numbers, true).then_some(value))
-        .sum::<usize>()
-        .to_string()
+    par_map(&equations, |(value, numbers)| {
+        if find_operators(value, 0, numbers, true) { *value } else { 0 }
+    })
+    .iter()
+    .sum::<usize>()
+    .to_string()
 }
 
 fn parse_input(input: &Vec<&str>) -> Vec<(usize, Vec<usize>)> {
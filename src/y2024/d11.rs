@@ -1,25 +1,23 @@
 //! Day 11: Plutonian Pebbles
 
-use std::collections::HashMap;
+use crate::{trace, utils::memo::Memo};
 
 /// number of stones after applying rules 25 times
 pub fn a(input: &Vec<&str>) -> String {
     let stones = parse_input(input);
-    stones
-        .iter()
-        .map(|stone| simulate(*stone, 25, &mut Cache::new()))
-        .sum::<usize>()
-        .to_string()
+    let mut memo = Cache::new();
+    let count = stones.iter().map(|&stone| simulate(stone, 25, &mut memo)).sum::<usize>();
+    trace::debug!("cache: {memo}");
+    count.to_string()
 }
 
 /// number of stones after applying rules 75 times
 pub fn b(input: &Vec<&str>) -> String {
     let stones = parse_input(input);
-    stones
-        .iter()
-        .map(|stone| simulate(*stone, 75, &mut Cache::new()))
-        .sum::<usize>()
-        .to_string()
+    let mut memo = Cache::new();
+    let count = stones.iter().map(|&stone| simulate(stone, 75, &mut memo)).sum::<usize>();
+    trace::debug!("cache: {memo}");
+    count.to_string()
 }
 
 fn parse_input(input: &Vec<&str>) -> Vec<usize> {
@@ -32,31 +30,24 @@ fn parse_input(input: &Vec<&str>) -> Vec<usize> {
 }
 
 /// determines how many stones this one turns into after given amount of blinks
-fn simulate(stone: usize, blinks: usize, cache: &mut Cache) -> usize {
-    if blinks == 0 {
-        return 1;
-    }
-
-    if let Some(result) = cache.get(&(stone, blinks)) {
-        return *result;
-    }
-
-    let result = if stone == 0 {
-        simulate(1, blinks - 1, cache)
-    } else if (stone.ilog10() + 1) % 2 == 0 {
-        let s = format!("{}", stone);
-        let halves = s.split_at(s.len() / 2);
-        simulate(halves.0.parse().unwrap(), blinks - 1, cache)
-            + simulate(halves.1.parse().unwrap(), blinks - 1, cache)
-    } else {
-        simulate(stone * 2024, blinks - 1, cache)
-    };
-
-    cache.insert((stone, blinks), result);
-    result
+fn simulate(stone: usize, blinks: usize, memo: &mut Cache) -> usize {
+    memo.get((stone, blinks), &|simulate, (stone, blinks)| {
+        if blinks == 0 {
+            1
+        } else if stone == 0 {
+            simulate((1, blinks - 1))
+        } else if (stone.ilog10() + 1).is_multiple_of(2) {
+            let s = format!("{}", stone);
+            let halves = s.split_at(s.len() / 2);
+            simulate((halves.0.parse().unwrap(), blinks - 1))
+                + simulate((halves.1.parse().unwrap(), blinks - 1))
+        } else {
+            simulate((stone * 2024, blinks - 1))
+        }
+    })
 }
 
-type Cache = HashMap<(usize, usize), usize>;
+type Cache = Memo<(usize, usize), usize>;
 
 #[test]
 pub fn test() {
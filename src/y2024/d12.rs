@@ -1,5 +1,7 @@
 //! Day 12: Garden Groups
 
+use crate::utils::union_find::UnionFind;
+
 /// sum of products of area and perimeter of each contiguous region
 pub fn a(input: &Vec<&str>) -> String {
     calculate_fence_price(input, false).to_string()
@@ -13,82 +15,57 @@ pub fn b(input: &Vec<&str>) -> String {
 fn calculate_fence_price(input: &Vec<&str>, with_sides: bool) -> usize {
     let w = input[0].len();
     let h = input.len();
+    let plant = |x: isize, y: isize| {
+        (x >= 0 && y >= 0 && (x as usize) < w && (y as usize) < h)
+            .then(|| input[y as usize].as_bytes()[x as usize])
+    };
 
-    // 2 levels of BFS to flood-fill areas of same type
-    let mut regions = vec![(0usize, 0usize)];
-    let mut visited = vec![vec![false; input[0].len()]; input.len()];
-    let mut cost = 0;
-    while let Some(region) = regions.pop() {
-        if visited[region.1][region.0] {
-            continue;
-        }
-        let mut pending = vec![region];
-        let mut area = 0;
-        let mut perimeter = 0;
-        let mut sides = 0;
-        let region_type = input[region.1].as_bytes()[region.0];
-        while let Some(pos) = pending.pop() {
-            if visited[pos.1][pos.0] {
-                continue;
+    // group neighbouring plots of same type into regions
+    let mut regions = UnionFind::new(w * h);
+    for y in 0..h {
+        for x in 0..w {
+            let region_type = input[y].as_bytes()[x];
+            if x + 1 < w && input[y].as_bytes()[x + 1] == region_type {
+                regions.union(y * w + x, y * w + x + 1);
             }
-            area += 1;
-            visited[pos.1][pos.0] = true;
-
-            let dir = [(1isize, 0isize), (0, 1), (-1, 0), (0, -1)];
-            let mut is_fence = vec![true; 4];
-            for (i, &(dx, dy)) in dir.iter().enumerate() {
-                // check grid boundaries
-                if (pos.0 == 0 && dx < 0) || (pos.0 + 1 == w && dx > 0) {
-                    perimeter += 1;
-                    continue;
-                }
-                if (pos.1 == 0 && dy < 0) || (pos.1 + 1 == h && dy > 0) {
-                    perimeter += 1;
-                    continue;
-                }
-
-                let next = (
-                    pos.0.checked_add_signed(dx).unwrap(),
-                    pos.1.checked_add_signed(dy).unwrap(),
-                );
-                let next_type = input[next.1].as_bytes()[next.0];
-                let next_visited = visited[next.1][next.0];
-                if next_type == region_type {
-                    // matching plant type
-                    is_fence[i] = false;
-                    if !next_visited {
-                        pending.push(next);
-                    }
-                } else {
-                    // different plant type, need fence
-                    perimeter += 1;
-                    if !next_visited {
-                        regions.push(next);
-                    }
-                }
+            if y + 1 < h && input[y + 1].as_bytes()[x] == region_type {
+                regions.union(y * w + x, (y + 1) * w + x);
             }
+        }
+    }
 
+    // collect fences of each plot in its region
+    let dir = [(1isize, 0isize), (0, 1), (-1, 0), (0, -1)];
+    let mut fences = vec![0; w * h];
+    for y in 0..h {
+        for x in 0..w {
+            let (px, py) = (x as isize, y as isize);
+            let region_type = plant(px, py);
+            let is_fence = dir.map(|(dx, dy)| plant(px + dx, py + dy) != region_type);
+            let root = regions.find(y * w + x);
             if with_sides {
-                // check how many corners this plot has
+                // each corner of the region starts a new side
                 for i in 0..4 {
                     let j = (i + 1) % 4;
-                    if is_fence[i] && is_fence[j] {
-                        // convex corner
-                        sides += 1;
-                    } else if !is_fence[i]
-                        && !is_fence[j]
-                        && input[pos.1.checked_add_signed(dir[i].1 + dir[j].1).unwrap()].as_bytes()
-                            [pos.0.checked_add_signed(dir[i].0 + dir[j].0).unwrap()]
-                            != region_type
+                    let diagonal = plant(px + dir[i].0 + dir[j].0, py + dir[i].1 + dir[j].1);
+                    if (is_fence[i] && is_fence[j])
+                        || (!is_fence[i] && !is_fence[j] && diagonal != region_type)
                     {
-                        // concave corner
-                        sides += 1;
+                        // convex or concave corner
+                        fences[root] += 1;
                     }
                 }
+            } else {
+                fences[root] += is_fence.iter().filter(|&&fence| fence).count();
             }
         }
-        // println!("region {} has area {area} and perimeter {perimeter} and sides {sides}", region_type as char);
-        cost += area * if with_sides { sides } else { perimeter };
+    }
+
+    let mut cost = 0;
+    for (plot, fences) in fences.into_iter().enumerate() {
+        if regions.find(plot) == plot {
+            cost += regions.size(plot) * fences;
+        }
     }
     cost
 }
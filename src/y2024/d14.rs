@@ -1,5 +1,7 @@
 //! Day 14: Restroom Redoubt
 
+use crate::visual::{self, Frame};
+
 /// safety factor after 100 robot movements
 pub fn a(input: &Vec<&str>) -> String {
     let robots = parse_input(input);
@@ -16,6 +18,7 @@ pub fn b(input: &Vec<&str>) -> String {
         let dist = (mean - score).abs();
         if dist > acc.1 { (i, dist) } else { acc }
     });
+    visual::emit(|| render(&robots, step as isize).with_caption(format!("step {step}")));
     step.to_string()
 }
 
@@ -40,6 +43,17 @@ fn simulate(robots: &Robots, steps: isize) -> isize {
     quadrants.iter().product()
 }
 
+fn render(robots: &Robots, steps: isize) -> Frame {
+    let (w, h) = if robots.len() == 12 { (11, 7) } else { (101, 103)};
+    let mut frame = Frame::new(w as usize, h as usize);
+    for robot in robots {
+        let x = (robot.0.0 + robot.1.0 * steps).rem_euclid(w);
+        let y = (robot.0.1 + robot.1.1 * steps).rem_euclid(h);
+        frame.set(x as usize, y as usize, b'#');
+    }
+    frame
+}
+
 type Pos = (isize, isize);
 type Robots = Vec<(Pos, Pos)>;
 
@@ -1,5 +1,7 @@
 //! Day 15: Warehouse Woes
 
+use crate::visual::{self, Frame};
+
 /// box coords after applying movements
 pub fn a(input: &Vec<&str>) -> String {
     let (mut map, start, moves) = parse_input(input, false);
@@ -75,25 +77,7 @@ fn simulate(map: &mut Map, start: &Pos, moves: &Moves) -> usize {
                 pos.1.checked_add_signed(dy).unwrap(),
             );
         }
-        // for (y, row) in map.iter().enumerate() {
-        //     println!(
-        //         "{}",
-        //         row.iter()
-        //             .enumerate()
-        //             .map(|(x, tile)| if (x, y) == pos {
-        //                 '@'
-        //             } else {
-        //                 match tile {
-        //                     Tile::Empty => '.',
-        //                     Tile::Wall => '#',
-        //                     Tile::Box => 'O',
-        //                     Tile::BoxL => '[',
-        //                     Tile::BoxR => ']',
-        //                 }
-        //             })
-        //             .collect::<String>()
-        //     );
-        // }
+        visual::emit(|| render(map, &pos));
     }
     map.iter()
         .enumerate()
@@ -109,6 +93,20 @@ fn simulate(map: &mut Map, start: &Pos, moves: &Moves) -> usize {
         .sum()
 }
 
+fn render(map: &Map, pos: &Pos) -> Frame {
+    let mut frame = Frame::from_rows(map.iter().map(|row| {
+        row.iter().map(|tile| match tile {
+            Tile::Empty => b'.',
+            Tile::Wall => b'#',
+            Tile::Box => b'O',
+            Tile::BoxL => b'[',
+            Tile::BoxR => b']',
+        })
+    }));
+    frame.set(pos.0, pos.1, b'@');
+    frame
+}
+
 fn try_move(map: &mut Map, pos: &Pos, dx: isize, dy: isize, dryrun: bool) -> bool {
     if !dryrun && !try_move(map, pos, dx, dy, true) {
         return false;
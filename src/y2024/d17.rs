@@ -1,93 +1,202 @@
 //! Day 17: Chronospatial Computer
 
+use std::fmt;
+
+use crate::vm::{self, Decode, Flow, Machine};
+
 /// determine program output
 pub fn a(input: &Vec<&str>) -> String {
-    let mut machine = parse_input(input);
+    let (registers, code) = parse_input(input);
+    let program = decode(&code);
+    let mut machine = Machine::new(&program, registers);
     machine.run();
-    machine.format_output()
+    format_output(&machine.state.out)
 }
 
 /// lowest possible A so that output equals program
 pub fn b(input: &Vec<&str>) -> String {
-    /* Observations:
+    /* Observations (checked by `loop_shift`):
      *   - program loops while A != 0
      *   - loop state is only kept in A
-     *   - each iteration consumes last 3 bits of A
+     *   - each iteration consumes the last few bits of A (3 for the puzzle input)
      *   - each iteration prints B mod 8, with B calculated from A
      */
 
-    let mut machine = parse_input(input);
-    let mut a = 0;
-    for tail in (0..machine.prog.len()).rev() {
-        // build A iteratively for groups of 3 bits
-        for i in 0..8 {
-            // find the 3 bit number that matches the end of the program
-            let candidate = a << 3 | i;
-            machine.a = candidate;
-            machine.ip = 0;
-            machine.out.clear();
-            machine.run();
-            if machine.out[..] == machine.prog[tail..] {
-                // println!("{candidate} {}", machine.format_output());
-                a = candidate;
-                break;
-            }
-        }
-    }
+    let (registers, code) = parse_input(input);
+    let program = decode(&code);
+    let shift = loop_shift(&program).unwrap_or_else(|err| panic!("unsupported program: {err}"));
+    let mut machine = Machine::new(&program, registers);
+    // run single iterations, the output of later ones is fixed by the upper bits
+    machine.add_breakpoint(program.len() - 1);
+    find_quine(&mut machine, &code, shift, code.len(), 0)
+        .expect("no value of A reproduces the program")
+        .to_string()
+}
 
-    a.to_string()
+/// program as annotated pseudo-code
+pub fn disassemble(input: &Vec<&str>) -> String {
+    let (_, code) = parse_input(input);
+    vm::disassemble(&decode(&code))
 }
 
-fn parse_input(input: &Vec<&str>) -> Machine {
-    Machine {
+fn parse_input(input: &Vec<&str>) -> (Registers, Vec<u8>) {
+    let registers = Registers {
         a: input[0][12..].parse().unwrap(),
         b: input[1][12..].parse().unwrap(),
         c: input[2][12..].parse().unwrap(),
-        ip: 0,
-        prog: input[4][9..]
-            .split(',')
-            .map(|n| n.parse().unwrap())
-            .collect(),
         out: Vec::new(),
+    };
+    let code = input[4][9..].split(',').map(|n| n.parse().unwrap()).collect();
+    (registers, code)
+}
+
+fn decode(code: &[u8]) -> Vec<Instruction> {
+    code.chunks_exact(2).map(Instruction::decode).collect()
+}
+
+/// Number of bits of A consumed per iteration of the program loop
+fn loop_shift(program: &[Instruction]) -> Result<usize, &'static str> {
+    let Some((last, body)) = program.split_last() else {
+        return Err("empty program");
+    };
+    if last.op != Op::Jnz || last.operand != 0 || body.iter().any(|inst| inst.op == Op::Jnz) {
+        return Err("program is not a single loop jumping back to the start");
+    }
+    if body.iter().filter(|inst| inst.op == Op::Out).count() != 1 {
+        return Err("loop does not output exactly one value");
+    }
+    let shifts = body.iter().filter(|inst| inst.op == Op::Adv).collect::<Vec<_>>();
+    let shift = match shifts[..] {
+        [inst] if (1..=3).contains(&inst.operand) => inst.operand as usize,
+        [_] => return Err("A is not shifted by a literal"),
+        _ => return Err("A is not shifted exactly once"),
+    };
+    // B and C have to be set in each iteration before being used
+    let mut written = [false; 2];
+    for inst in body {
+        let combo = [5, 6].map(|reg| inst.operand == reg);
+        let reads = match inst.op {
+            Op::Bxl | Op::Bxc => [true, inst.op == Op::Bxc],
+            Op::Adv | Op::Bst | Op::Out | Op::Bdv | Op::Cdv => combo,
+            Op::Jnz => [false, false],
+        };
+        if reads.iter().zip(written).any(|(&read, written)| read && !written) {
+            return Err("B or C keep state between iterations");
+        }
+        match inst.op {
+            Op::Bxl | Op::Bst | Op::Bxc | Op::Bdv => written[0] = true,
+            Op::Cdv => written[1] = true,
+            _ => {}
+        }
+    }
+    Ok(shift)
+}
+
+/// Lowest A whose output ends with `code[tail..]`, given its upper bits `a`
+///
+/// Candidates for the lower bits are tried in increasing order, backtracking
+/// if no later iteration matches.
+fn find_quine(
+    machine: &mut Machine<Instruction>,
+    code: &[u8],
+    shift: usize,
+    tail: usize,
+    a: usize,
+) -> Option<usize> {
+    if tail == 0 {
+        return Some(a);
     }
+    for i in 0..1 << shift {
+        // a zero value would end the loop early
+        let candidate = a << shift | i;
+        if candidate == 0 {
+            continue;
+        }
+        machine.reset(Registers {
+            a: candidate,
+            b: 0,
+            c: 0,
+            out: Vec::new(),
+        });
+        machine.run();
+        if machine.state.out[..] == code[tail - 1..tail]
+            && let Some(a) = find_quine(machine, code, shift, tail - 1, candidate)
+        {
+            return Some(a);
+        }
+    }
+    None
+}
+
+fn format_output(out: &[u8]) -> String {
+    out.iter().map(|&b| String::from((b'0' + b) as char)).collect::<Vec<_>>().join(",")
 }
 
-impl Machine {
-    fn run(&mut self) {
-        while self.ip + 1 < self.prog.len() {
-            self.step()
+impl Decode<&[u8]> for Instruction {
+    fn decode(code: &[u8]) -> Self {
+        Instruction {
+            op: code[0].into(),
+            operand: code[1],
         }
     }
+}
+
+impl vm::Instruction for Instruction {
+    type State = Registers;
 
-    fn step(&mut self) {
-        let op = self.op();
-        let val = self.prog[self.ip + 1];
-        // println!(
-        //     "{:x}: {op:?} {val} (A={}, B={}, C={})",
-        //     self.ip, self.a, self.b, self.c
-        // );
-        match op {
-            Op::Adv => self.a /= 1 << self.combo(val),
-            Op::Bxl => self.b ^= val as usize,
-            Op::Bst => self.b = self.combo(val) % 8,
+    fn execute(&self, reg: &mut Registers) -> Flow {
+        let val = self.operand;
+        match self.op {
+            Op::Adv => reg.a /= 1 << reg.combo(val),
+            Op::Bxl => reg.b ^= val as usize,
+            Op::Bst => reg.b = reg.combo(val) % 8,
             Op::Jnz => {
-                if self.a != 0 {
-                    self.ip = val as usize;
-                    return;
+                if reg.a != 0 {
+                    // instructions are two words long
+                    assert!(val.is_multiple_of(2), "unaligned jump");
+                    return Flow::Goto(val as usize / 2);
                 }
             }
-            Op::Bxc => self.b ^= self.c,
-            Op::Out => self.out.push((self.combo(val) % 8) as u8),
-            Op::Bdv => self.b = self.a / (1 << self.combo(val)),
-            Op::Cdv => self.c = self.a / (1 << self.combo(val)),
+            Op::Bxc => reg.b ^= reg.c,
+            Op::Out => reg.out.push((reg.combo(val) % 8) as u8),
+            Op::Bdv => reg.b = reg.a / (1 << reg.combo(val)),
+            Op::Cdv => reg.c = reg.a / (1 << reg.combo(val)),
+        }
+        Flow::Next
+    }
+
+    fn successors(&self, ip: usize) -> Vec<usize> {
+        match self.op {
+            Op::Jnz => vec![self.operand as usize / 2, ip + 1],
+            _ => vec![ip + 1],
         }
-        self.ip += 2;
     }
+}
 
-    fn op(&self) -> Op {
-        self.prog[self.ip].into()
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let val = self.operand;
+        let combo = match val {
+            0..=3 => val.to_string(),
+            4 => "a".to_owned(),
+            5 => "b".to_owned(),
+            6 => "c".to_owned(),
+            _ => "?".to_owned(),
+        };
+        match self.op {
+            Op::Adv => write!(f, "a >>= {combo}"),
+            Op::Bxl => write!(f, "b ^= {val}"),
+            Op::Bst => write!(f, "b = {combo} % 8"),
+            Op::Jnz => write!(f, "if a != 0 goto {}", val / 2),
+            Op::Bxc => write!(f, "b ^= c"),
+            Op::Out => write!(f, "out({combo} % 8)"),
+            Op::Bdv => write!(f, "b = a >> {combo}"),
+            Op::Cdv => write!(f, "c = a >> {combo}"),
+        }
     }
+}
 
+impl Registers {
     fn combo(&self, val: u8) -> usize {
         match val {
             0..=3 => val as usize,
@@ -97,14 +206,6 @@ impl Machine {
             _ => unreachable!(),
         }
     }
-
-    fn format_output(&self) -> String {
-        self.out
-            .iter()
-            .map(|&b| String::from((b'0' + b) as char))
-            .collect::<Vec<_>>()
-            .join(",")
-    }
 }
 
 impl From<u8> for Op {
@@ -143,13 +244,16 @@ enum Op {
     Cdv,
 }
 
+struct Instruction {
+    op: Op,
+    operand: u8,
+}
+
 #[derive(Clone, Debug)]
-struct Machine {
+struct Registers {
     a: usize,
     b: usize,
     c: usize,
-    ip: usize,
-    prog: Vec<u8>,
     out: Vec<u8>,
 }
 
@@ -163,7 +267,7 @@ pub fn test() {
         "Program: 0,1,5,4,3,0",
     ];
 
-    let _input2 = vec![
+    let input2 = vec![
         "Register A: 2024",
         "Register B: 0",
         "Register C: 0",
@@ -172,5 +276,13 @@ pub fn test() {
     ];
 
     assert_eq!(a(&input1), "4,6,3,5,6,3,5,2,1,0");
-    // assert_eq!(b(&input2), "117440");
+    assert_eq!(b(&input2), "117440");
+    let mut quine = input2.clone();
+    quine[0] = "Register A: 117440";
+    assert_eq!(a(&quine), "0,3,5,4,3,0");
+
+    assert_eq!(loop_shift(&decode(&[0, 1, 5, 4, 3, 0])), Ok(1));
+    assert!(loop_shift(&decode(&[0, 4, 5, 4, 3, 0])).is_err()); // shift by A
+    assert!(loop_shift(&decode(&[0, 3, 5, 4])).is_err()); // no loop
+    assert!(loop_shift(&decode(&[0, 3, 1, 2, 5, 5, 3, 0])).is_err()); // B carried over
 }
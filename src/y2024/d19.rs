@@ -1,6 +1,6 @@
 //! Day 19: Linen Layout
 
-use std::collections::HashMap;
+use crate::{trace, utils::memo::Memo};
 
 /// possible designs with available patterns
 pub fn a(input: &Vec<&str>) -> String {
@@ -11,8 +11,10 @@ pub fn a(input: &Vec<&str>) -> String {
 /// possible ways to make each design
 pub fn b(input: &Vec<&str>) -> String {
     let patterns = input[0].split(", ").collect::<Vec<_>>();
-    let mut cache = HashMap::new();
-    input[2..].iter().map(|design| count_designs(&patterns, &mut cache, design)).sum::<usize>().to_string()
+    let mut memo = Memo::new();
+    let count = input[2..].iter().map(|design| count_designs(&patterns, &mut memo, design)).sum::<usize>();
+    trace::debug!("cache: {memo}");
+    count.to_string()
 }
 
 fn check_design(patterns: &Vec<&str>, design: &str) -> bool {
@@ -27,21 +29,17 @@ fn check_design(patterns: &Vec<&str>, design: &str) -> bool {
     false
 }
 
-fn count_designs<'a>(patterns: &Vec<&str>, cache: &mut HashMap<&'a str, usize>, design: &'a str) -> usize {
-    if design.is_empty() {
-        return 1;
-    }
-    if let Some(hit) = cache.get(design) {
-        return *hit;
-    }
-    let mut count = 0;
-    for pattern in patterns {
-        if pattern.len() <= design.len() && design.starts_with(pattern) {
-            count += count_designs(patterns, cache, &design[pattern.len()..]);
+fn count_designs<'a>(patterns: &Vec<&str>, memo: &mut Memo<&'a str, usize>, design: &'a str) -> usize {
+    memo.get(design, &|count_designs, design| {
+        if design.is_empty() {
+            return 1;
         }
-    }
-    cache.insert(design, count);
-    count
+        patterns
+            .iter()
+            .filter_map(|pattern| design.strip_prefix(pattern))
+            .map(&mut *count_designs)
+            .sum()
+    })
 }
 
 #[test]
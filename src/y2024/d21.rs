@@ -1,31 +1,33 @@
 //! Day 21: Keypad Conundrum
 
-use std::collections::HashMap;
+use crate::{trace, utils::memo::Memo};
 
 /// sum of complexities of shortest button sequences across chain of 3 robots
 pub fn a(input: &Vec<&str>) -> String {
-    let mut cache = Cache::new();
-    input
+    let mut memo = Memo::new();
+    let sum = input
         .iter()
         .map(|code| {
-            let len = presses_on_dirpad(NUMPAD, &parse_code(code), 3, &mut cache);
+            let len = presses_on_dirpad(NUMPAD, &parse_code(code), 3, &mut memo);
             complexity(code, len)
         })
-        .sum::<usize>()
-        .to_string()
+        .sum::<usize>();
+    trace::debug!("cache: {memo}");
+    sum.to_string()
 }
 
 /// sum of complexities of shortest button sequences across chain of 26 robots
 pub fn b(input: &Vec<&str>) -> String {
-    let mut cache = Cache::new();
-    input
+    let mut memo = Memo::new();
+    let sum = input
         .iter()
         .map(|code| {
-            let len = presses_on_dirpad(NUMPAD, &parse_code(code), 26, &mut cache);
+            let len = presses_on_dirpad(NUMPAD, &parse_code(code), 26, &mut memo);
             complexity(code, len)
         })
-        .sum::<usize>()
-        .to_string()
+        .sum::<usize>();
+    trace::debug!("cache: {memo}");
+    sum.to_string()
 }
 
 /// converts ASCII character to numeric keypad index
@@ -45,29 +47,42 @@ fn parse_code(code: &str) -> Vec<usize> {
 }
 
 /// translates sequence via graph to directional keypad presses
-fn presses_on_dirpad(keypad: &str, source: &Sequence, depth: usize, cache: &mut Cache) -> usize {
-    #[cfg(test)]
-    print_sequence(keypad, &source);
+fn presses_on_dirpad(
+    keypad: &'static str,
+    source: &Sequence,
+    depth: usize,
+    memo: &mut Memo<Move, usize>,
+) -> usize {
+    trace::trace!("{}", format_sequence(keypad, source));
 
     if depth == 0 {
         return source.len();
     }
+    moves(keypad, source)
+        .map(|(from, to)| memo.get((keypad, depth, from, to), &move_presses))
+        .sum()
+}
+
+/// presses on the outermost directional keypad to move between two buttons and press the second
+fn move_presses(recurse: &mut dyn FnMut(Move) -> usize, (keypad, depth, from, to): Move) -> usize {
+    let mut seq = find_path(keypad, &from, &to);
+    seq.push(2); // A
+    trace::trace!("{}", format_sequence(DIRPAD, &seq));
 
-    let mut len = 0;
-    let mut curr = if keypad.len() == 6 { 2 } else { 11 };
-    for src in source {
-        if let Some(n) = cache.get(&(depth, curr, *src)) {
-            len += *n;
-        } else {
-            let mut seq = find_path(keypad, &curr, src);
-            seq.push(2); // A
-            let n = presses_on_dirpad(DIRPAD, &seq, depth - 1, cache);
-            len += n;
-            cache.insert((depth, curr, *src), n);
-        }
-        curr = *src;
+    if depth == 1 {
+        return seq.len();
     }
-    len
+    moves(DIRPAD, &seq)
+        .map(|(from, to)| recurse((DIRPAD, depth - 1, from, to)))
+        .sum()
+}
+
+/// pairs of consecutive buttons, starting on the activate button
+fn moves<'a>(keypad: &str, seq: &'a Sequence) -> impl Iterator<Item = (usize, usize)> + 'a {
+    let start = if keypad.len() == 6 { 2 } else { 11 };
+    std::iter::once(start)
+        .chain(seq.iter().copied())
+        .zip(seq.iter().copied())
 }
 
 /// cheapest path through keypad graph
@@ -104,21 +119,16 @@ fn complexity(code: &str, len: usize) -> usize {
     len * code[0..3].parse::<usize>().unwrap()
 }
 
-#[cfg(test)]
 fn format_sequence(keypad: &str, seq: &Sequence) -> String {
     seq.iter().map(|&i| &keypad[i..=i]).collect()
 }
 
-#[cfg(test)]
-fn print_sequence(keypad: &str, seq: &Sequence) {
-    println!("{}", format_sequence(keypad, seq));
-}
-
 const NUMPAD: &str = "789456123 0A";
 const DIRPAD: &str = " ^A<v>";
 
 type Sequence = Vec<usize>;
-type Cache = HashMap<(usize, usize, usize), usize>;
+/// keypad, remaining robots, button pressed last and next
+type Move = (&'static str, usize, usize, usize);
 
 #[test]
 pub fn test() {
@@ -156,9 +166,9 @@ pub fn test() {
         ">>vvv"
     );
 
-    assert_eq!(presses_on_dirpad(NUMPAD, &code, 1, &mut Cache::new()), 12);
-    assert_eq!(presses_on_dirpad(NUMPAD, &code, 2, &mut Cache::new()), 28);
-    assert_eq!(presses_on_dirpad(NUMPAD, &code, 3, &mut Cache::new()), 68);
+    assert_eq!(presses_on_dirpad(NUMPAD, &code, 1, &mut Memo::new()), 12);
+    assert_eq!(presses_on_dirpad(NUMPAD, &code, 2, &mut Memo::new()), 28);
+    assert_eq!(presses_on_dirpad(NUMPAD, &code, 3, &mut Memo::new()), 68);
 
     assert_eq!(a(&input), "126384");
     // assert_eq!(b(&input), "");
@@ -1,13 +1,13 @@
 //! Day 22: Monkey Market
 
+use crate::utils::parallel::{self, par_map};
 use std::collections::HashMap;
 
 /// sum of 2000th secret numbers
 pub fn a(input: &Vec<&str>) -> String {
     let numbers = parse_input(input);
-    numbers
+    par_map(&numbers, |&n| secrefy(n, 2000))
         .iter()
-        .map(|n| secrefy(*n, 2000))
         .sum::<usize>()
         .to_string()
 }
@@ -15,10 +15,12 @@ pub fn a(input: &Vec<&str>) -> String {
 /// best sequence of last digit price changes after which to sell
 pub fn b(input: &Vec<&str>) -> String {
     let numbers = parse_input(input);
+    // one batch of buyers per thread, each adding up its own totals
+    let batch_size = numbers.len().div_ceil(parallel::threads()).max(1);
+    let batches = numbers.chunks(batch_size).collect::<Vec<_>>();
     let mut sequences = Sequences::new();
-    for n in numbers {
-        let seq = build_sequences(n, 2000);
-        for (changes, price) in seq {
+    for batch in par_map(&batches, |batch| add_sequences(batch)) {
+        for (changes, price) in batch {
             sequences
                 .entry(changes)
                 .and_modify(|e| *e += price)
@@ -28,6 +30,20 @@ pub fn b(input: &Vec<&str>) -> String {
     sequences.values().max().unwrap().to_string()
 }
 
+/// total prices of each sequence of changes over all buyers
+fn add_sequences(numbers: &[usize]) -> Sequences {
+    let mut sequences = Sequences::new();
+    for &n in numbers {
+        for (changes, price) in build_sequences(n, 2000) {
+            sequences
+                .entry(changes)
+                .and_modify(|e| *e += price)
+                .or_insert(price);
+        }
+    }
+    sequences
+}
+
 fn parse_input(input: &Vec<&str>) -> Vec<usize> {
     input.iter().map(|s| s.parse().unwrap()).collect()
 }
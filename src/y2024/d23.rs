@@ -1,21 +1,16 @@
 //! Day 23: LAN Party
 
-use std::collections::{HashMap, HashSet};
+use crate::{dot, utils::graph::Graph};
 
 /// interconnected sets of 3 nodes involving one starting with `t`
 pub fn a(input: &Vec<&str>) -> String {
     let graph = parse_input(input);
+    let historian = |idx| graph.label(idx).starts_with('t');
     let mut count = 0;
-    for (node, adj) in &graph {
-        for a in adj {
-            if a < node {
-                continue;
-            }
-            for b in &graph[*a] {
-                if b > a
-                    && (node.starts_with('t') || a.starts_with('t') || b.starts_with('t'))
-                    && graph[*b].contains(node)
-                {
+    for node in 0..graph.len() {
+        for a in graph.neighbors(node).filter(|&a| a > node) {
+            for b in graph.neighbors(a).filter(|&b| b > a) {
+                if (historian(node) || historian(a) || historian(b)) && graph.has_edge(b, node) {
                     // found group of 3
                     count += 1;
                 }
@@ -28,72 +23,38 @@ pub fn a(input: &Vec<&str>) -> String {
 /// sorted nodes in largest fully connected subgraph
 pub fn b(input: &Vec<&str>) -> String {
     let graph = parse_input(input);
-    let mut subgraph = Nodes::new();
-    max_cliques(
-        &graph,
-        &Nodes::new(),
-        graph.keys().copied().collect(),
-        Nodes::new(),
-        &mut subgraph,
-    );
-    let mut subgraph = Vec::from_iter(subgraph.into_iter());
+    let mut subgraph = graph
+        .max_clique()
+        .into_iter()
+        .map(|idx| graph.label(idx))
+        .collect::<Vec<_>>();
     subgraph.sort_unstable();
     subgraph.join(",")
 }
 
-fn parse_input<'a>(input: &'a Vec<&'a str>) -> Graph<'a> {
-    // build undirected graph
-    let mut graph = Graph::new();
+fn parse_input<'a>(input: &[&'a str]) -> Graph<&'a str> {
+    dot::export(|| {
+        let mut graph = dot::Graph::undirected("lan");
+        for s in input {
+            let (a, b) = s.split_once('-').unwrap();
+            graph.edge(a, b);
+        }
+        for s in input {
+            for node in s.split('-').filter(|node| node.starts_with('t')) {
+                graph.node(node).kind("historian");
+            }
+        }
+        graph
+    });
+
+    let mut graph = Graph::undirected();
     for s in input {
         let (a, b) = s.split_once('-').unwrap();
-        graph
-            .entry(a)
-            .and_modify(|adj| {
-                adj.insert(b);
-            })
-            .or_insert(HashSet::from([b]));
-        graph
-            .entry(b)
-            .and_modify(|adj| {
-                adj.insert(a);
-            })
-            .or_insert(HashSet::from([a]));
+        graph.edge(a, b);
     }
     graph
 }
 
-/// Bron-Kerbosch algorithm
-/// https://en.wikipedia.org/wiki/Bron%E2%80%93Kerbosch_algorithm
-fn max_cliques<'a>(
-    graph: &Graph<'a>,
-    r: &Nodes<'a>,
-    mut p: Nodes<'a>,
-    mut x: Nodes<'a>,
-    max: &mut Nodes<'a>,
-) {
-    if p.is_empty() && x.is_empty() {
-        if r.len() > max.len() {
-            *max = r.to_owned();
-        }
-        return;
-    }
-    for v in p.to_owned() {
-        let adj = &graph[v];
-        max_cliques(
-            graph,
-            &r.union(&HashSet::from([v])).copied().collect(),
-            p.intersection(&adj).copied().collect(),
-            x.intersection(&adj).copied().collect(),
-            max,
-        );
-        p.remove(v);
-        x.insert(v);
-    }
-}
-
-type Nodes<'a> = HashSet<&'a str>;
-type Graph<'a> = HashMap<&'a str, Nodes<'a>>;
-
 #[test]
 pub fn test() {
     let input = vec![
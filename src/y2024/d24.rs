@@ -1,6 +1,7 @@
 //! Day 24: Crossed Wires
 
-use std::collections::HashMap;
+use crate::{dot, trace, utils};
+use std::collections::{HashMap, HashSet};
 
 /// decimal number calculated by logic circuit
 pub fn a(input: &Vec<&str>) -> String {
@@ -20,10 +21,26 @@ pub fn a(input: &Vec<&str>) -> String {
 
 /// swap output wires of 4 pairs of gates to make circuit a ripple-carry adder
 pub fn b(input: &Vec<&str>) -> String {
+    let (_, mut graph) = parse_input(input);
+    let candidates = find_swap_candidates(&graph);
+    let swaps = pair_up(&mut graph, &candidates, &mut Vec::new())
+        .or_else(|| {
+            trace::debug!("rules found no valid swaps in {candidates:?}, searching");
+            let mut swaps = Vec::new();
+            search_swaps(&mut graph, &mut swaps).then_some(swaps)
+        })
+        .expect("failed to repair circuit");
+
+    let mut wires = swaps.iter().flat_map(|&(u, v)| [u, v]).collect::<Vec<_>>();
+    wires.sort_unstable();
+    wires.join(",")
+}
+
+/// wires violating the structure of a ripple-carry adder
+fn find_swap_candidates<'a>(graph: &Graph<'a>) -> Vec<&'a str> {
     // half adder: inputs XOR'd is result bit, inputs AND'd is carry bit
     // full adder: second half adder - prev result with input XOR'd is result, carry bits get OR'd
     // -> results always follows XOR (except last), which (except first) follows XOR and OR, with xor following two inputs and OR following two ANDs
-    let (_, graph) = parse_input(input);
     let outputs = graph
         .keys()
         .filter(|wire| wire.starts_with("z"))
@@ -37,7 +54,7 @@ pub fn b(input: &Vec<&str>) -> String {
         }
         let gate = &graph[output];
         if gate.op != Op::Xor {
-            println!("! {output} follows {:?}", gate.op);
+            trace::debug!("! {output} follows {:?}", gate.op);
             swaps.push(*output);
             continue;
         }
@@ -46,7 +63,7 @@ pub fn b(input: &Vec<&str>) -> String {
             graph.get_key_value(gate.in2).unwrap(),
         ] {
             if gate_in_gate.op == Op::And {
-                println!(
+                trace::debug!(
                     "! {output} follows {} follows {:?}",
                     gate_in_wire, gate_in_gate.op
                 );
@@ -54,7 +71,7 @@ pub fn b(input: &Vec<&str>) -> String {
             } else if gate_in_gate.op == Op::Xor {
                 for gate_in_gate_in_wire in [gate_in_gate.in1, gate_in_gate.in2] {
                     if ![b'x', b'y'].contains(&gate_in_gate_in_wire.as_bytes()[0]) {
-                        println!(
+                        trace::debug!(
                             "! {output} follows {} follows {} (not input)",
                             gate_in_wire, gate_in_gate_in_wire
                         );
@@ -68,7 +85,7 @@ pub fn b(input: &Vec<&str>) -> String {
                     graph.get_key_value(gate_in_gate.in2).unwrap(),
                 ] {
                     if gate_in_gate_in_gate.op != Op::And {
-                        println!(
+                        trace::debug!(
                             "! {output} follows {} follows {} follows {:?}",
                             gate_in_wire, gate_in_gate_in_wire, gate_in_gate_in_gate.op
                         );
@@ -80,7 +97,157 @@ pub fn b(input: &Vec<&str>) -> String {
     }
 
     swaps.sort_unstable();
-    swaps.join(",")
+    swaps.dedup();
+    swaps
+}
+
+/// tries all pairings of candidate wires, returns the swaps verified to make a valid adder
+fn pair_up<'a>(
+    graph: &mut Graph<'a>,
+    candidates: &[&'a str],
+    swaps: &mut Vec<(&'a str, &'a str)>,
+) -> Option<Vec<(&'a str, &'a str)>> {
+    let Some((&u, rest)) = candidates.split_first() else {
+        return (is_acyclic(graph) && first_error(graph).is_none()).then(|| swaps.to_owned());
+    };
+    for (i, &v) in rest.iter().enumerate() {
+        let rest = [&rest[..i], &rest[i + 1..]].concat();
+        swap_outputs(graph, u, v);
+        swaps.push((u, v));
+        let result = pair_up(graph, &rest, swaps);
+        swaps.pop();
+        swap_outputs(graph, u, v);
+        if result.is_some() {
+            return result;
+        }
+    }
+    None
+}
+
+/// backtracking search for up to 4 swaps, each fixing the lowest wrong output bit
+fn search_swaps<'a>(graph: &mut Graph<'a>, swaps: &mut Vec<(&'a str, &'a str)>) -> bool {
+    const MAX_SWAPS: usize = 4;
+    let Some(bit) = first_error(graph) else {
+        return true;
+    };
+    if swaps.len() == MAX_SWAPS {
+        return false;
+    }
+
+    // gates added to the circuit for this bit and the next one are the likeliest culprits
+    let z = |bit: usize| format!("z{bit:02}");
+    let mut local = cone(graph, &z(bit));
+    local.extend(cone(graph, &z(bit + 1)));
+    if bit > 0 {
+        for wire in cone(graph, &z(bit - 1)) {
+            local.remove(wire);
+        }
+    }
+    let mut local = local.into_iter().collect::<Vec<_>>();
+    local.sort_unstable();
+    let mut all = graph.keys().copied().collect::<Vec<_>>();
+    all.sort_unstable();
+
+    let pairs = local
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &u)| local[i + 1..].iter().map(move |&v| (u, v)))
+        .chain(local.iter().flat_map(|&u| {
+            all.iter().filter(|v| !local.contains(v)).map(move |&v| (u, v))
+        }))
+        .filter(|&(u, v)| !swaps.iter().any(|&(a, b)| [a, b].contains(&u) || [a, b].contains(&v)))
+        .collect::<Vec<_>>();
+
+    for (u, v) in pairs {
+        swap_outputs(graph, u, v);
+        if is_acyclic(graph) && first_error(graph).is_none_or(|b| b > bit) {
+            trace::debug!("swapping {u} and {v} fixes bit {bit}");
+            swaps.push((u, v));
+            if search_swaps(graph, swaps) {
+                return true;
+            }
+            swaps.pop();
+        }
+        swap_outputs(graph, u, v);
+    }
+    false
+}
+
+fn swap_outputs(graph: &mut Graph, u: &str, v: &str) {
+    let gate_u = graph[u];
+    let gate_v = std::mem::replace(graph.get_mut(v).unwrap(), gate_u);
+    graph.insert(graph.get_key_value(u).map(|(k, _)| *k).unwrap(), gate_v);
+}
+
+/// gate output wires that `wire` depends on, including itself
+fn cone<'a>(graph: &Graph<'a>, wire: &str) -> HashSet<&'a str> {
+    let mut cone = HashSet::new();
+    let mut pending = graph.get_key_value(wire).map(|(k, _)| *k).into_iter().collect::<Vec<_>>();
+    while let Some(wire) = pending.pop() {
+        if cone.insert(wire) {
+            let gate = &graph[wire];
+            pending.extend([gate.in1, gate.in2].iter().filter(|w| graph.contains_key(*w)));
+        }
+    }
+    cone
+}
+
+fn is_acyclic(graph: &Graph) -> bool {
+    // 1: in progress, 2: done
+    fn visit<'a>(wire: &'a str, graph: &Graph<'a>, marks: &mut HashMap<&'a str, u8>) -> bool {
+        match marks.get(wire) {
+            Some(1) => return false,
+            Some(_) => return true,
+            None => {}
+        }
+        let Some(gate) = graph.get(wire) else {
+            return true; // input wire
+        };
+        marks.insert(wire, 1);
+        let acyclic = visit(gate.in1, graph, marks) && visit(gate.in2, graph, marks);
+        marks.insert(wire, 2);
+        acyclic
+    }
+    let mut marks = HashMap::new();
+    graph.keys().all(|wire| visit(wire, graph, &mut marks))
+}
+
+/// simulates additions of edge case and pseudo-random operands, returns lowest wrong output bit
+fn first_error(graph: &Graph) -> Option<usize> {
+    let mut inputs = graph
+        .values()
+        .flat_map(|gate| [gate.in1, gate.in2])
+        .filter(|wire| wire.starts_with(['x', 'y']))
+        .collect::<Vec<_>>();
+    inputs.sort_unstable();
+    inputs.dedup();
+    let bits = inputs.len() / 2;
+    let mut outputs = graph.keys().filter(|wire| wire.starts_with('z')).copied().collect::<Vec<_>>();
+    outputs.sort_unstable();
+
+    let mask = (1u64 << bits) - 1;
+    let mut operands = vec![(0, 0), (mask, 0), (0, mask), (mask, mask), (mask, 1), (1, mask)];
+    for i in 0..bits {
+        operands.extend([(1 << i, 0), (0, 1 << i), (1 << i, 1 << i)]);
+    }
+    let mut rng = utils::Rng::new(24);
+    operands.extend((0..32).map(|_| (rng.next_u64() & mask, rng.next_u64() & mask)));
+
+    operands
+        .into_iter()
+        .filter_map(|(x, y)| {
+            let mut states = States::with_capacity(graph.len() + inputs.len());
+            for (i, wire) in inputs.iter().enumerate() {
+                let operand = if i < bits { x } else { y };
+                states.insert(wire, operand >> (i % bits) & 1 != 0);
+            }
+            let sum = x + y;
+            outputs
+                .iter()
+                .enumerate()
+                .find_map(|(i, wire)| (evaluate(wire, graph, &mut states) != (sum >> i & 1 != 0)).then_some(i))
+        })
+        .min()
 }
 
 fn parse_input<'a>(input: &'a Vec<&'a str>) -> (States<'a>, Graph<'a>) {
@@ -110,25 +277,50 @@ fn parse_input<'a>(input: &'a Vec<&'a str>) -> (States<'a>, Graph<'a>) {
             };
             parts.next(); // ->
             let out = parts.next().unwrap();
-            // println!("  {0} -> {0}_{2:?}_{1} [label=\"in1\"];\n  {1} -> {0}_{2:?}_{1} [label=\"in2\"];\n  {0}_{2:?}_{1} -> {3};\n  {0}_{2:?}_{1} [label=\"{2:?}\"];", gate.in1, gate.in2, gate.op, out);
             (out, gate)
         })
         .collect();
+    dot::export(|| circuit_graph(&states, &graph));
     (states, graph)
 }
 
-fn evaluate(wire: &str, graph: &Graph, states: &mut States) -> bool {
+/// wires and gates as nodes, with gates named after their output wire
+fn circuit_graph(states: &States, graph: &Graph) -> dot::Graph {
+    let mut dot = dot::Graph::directed("circuit");
+    let mut inputs = states.keys().collect::<Vec<_>>();
+    inputs.sort_unstable();
+    for wire in inputs {
+        dot.node(wire).kind("input");
+    }
+    let mut outputs = graph.keys().collect::<Vec<_>>();
+    outputs.sort_unstable();
+    for out in outputs {
+        let gate = &graph[out];
+        let op = format!("{:?}", gate.op).to_uppercase();
+        let id = format!("{op} {out}");
+        dot.node(&id).kind("gate").label(op);
+        dot.node(out).kind(if out.starts_with('z') { "output" } else { "wire" });
+        dot.edge(gate.in1, &id).label("in1");
+        dot.edge(gate.in2, &id).label("in2");
+        dot.edge(&id, out);
+    }
+    dot
+}
+
+fn evaluate<'a>(wire: &str, graph: &Graph<'a>, states: &mut States<'a>) -> bool {
     if let Some(state) = states.get(wire) {
         *state
     } else {
-        let gate = &graph[wire];
+        let (wire, gate) = graph.get_key_value(wire).unwrap();
         let val1 = evaluate(gate.in1, graph, states);
         let val2 = evaluate(gate.in2, graph, states);
-        match gate.op {
+        let state = match gate.op {
             Op::And => val1 & val2,
             Op::Or => val1 | val2,
             Op::Xor => val1 ^ val2,
-        }
+        };
+        states.insert(wire, state);
+        state
     }
 }
 
@@ -215,5 +407,51 @@ pub fn test() {
 
     assert_eq!(a(&input1), "4");
     assert_eq!(a(&input2), "2024");
-    // assert_eq!(b(&input), "");
+
+    // ripple-carry adder with swapped gate outputs
+    let adder = |bits: usize, swaps: &[(&str, &str)]| {
+        let mut lines = (0..bits)
+            .flat_map(|i| [format!("x{i:02}: 0"), format!("y{i:02}: 0")])
+            .collect::<Vec<_>>();
+        lines.push(String::new());
+        lines.push("x00 XOR y00 -> z00".to_owned());
+        lines.push("x00 AND y00 -> c00".to_owned());
+        for i in 1..bits {
+            let carry = if i + 1 == bits { format!("z{bits:02}") } else { format!("c{i:02}") };
+            lines.push(format!("x{i:02} XOR y{i:02} -> s{i:02}"));
+            lines.push(format!("x{i:02} AND y{i:02} -> a{i:02}"));
+            lines.push(format!("s{i:02} XOR c{:02} -> z{i:02}", i - 1));
+            lines.push(format!("s{i:02} AND c{:02} -> b{i:02}", i - 1));
+            lines.push(format!("a{i:02} OR b{i:02} -> {carry}"));
+        }
+        for line in lines.iter_mut() {
+            for &(u, v) in swaps {
+                if let Some(gate) = line.strip_suffix(u) {
+                    *line = format!("{gate}{v}");
+                } else if let Some(gate) = line.strip_suffix(v) {
+                    *line = format!("{gate}{u}");
+                }
+            }
+        }
+        lines
+    };
+
+    let input3 = adder(10, &[]);
+    let input3 = input3.iter().map(String::as_str).collect::<Vec<_>>();
+    let (_, graph) = parse_input(&input3);
+    assert!(is_acyclic(&graph));
+    assert_eq!(first_error(&graph), None);
+
+    let swaps = [("z03", "c03"), ("s05", "a05"), ("z07", "b07"), ("z08", "c09")];
+    let input4 = adder(12, &swaps);
+    let input4 = input4.iter().map(String::as_str).collect::<Vec<_>>();
+    let (_, mut graph) = parse_input(&input4);
+    assert_eq!(first_error(&graph), Some(3));
+    assert_eq!(b(&input4), "a05,b07,c03,c09,s05,z03,z07,z08");
+
+    // guided search without structural rules
+    let mut found = Vec::new();
+    assert!(search_swaps(&mut graph, &mut found));
+    assert_eq!(first_error(&graph), None);
+    assert_eq!(found.len(), 4);
 }
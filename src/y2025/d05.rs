@@ -1,47 +1,26 @@
 //! Day 5: Cafeteria
 
+use crate::utils::interval::{Interval, IntervalSet};
+
 /// number of IDs included in at least one range
 pub fn a(input: &Vec<&str>) -> String {
     let (ranges, ids) = parse_input(input);
-    ids.iter()
-        .filter(|id| ranges.iter().any(|(start, end)| *id >= start && *id <= end))
-        .count()
-        .to_string()
+    ids.iter().filter(|&&id| ranges.contains(id)).count().to_string()
 }
 
 /// size of merged ranges
 pub fn b(input: &Vec<&str>) -> String {
-    let (mut ranges, _) = parse_input(input);
-    ranges.sort_unstable();
-    let mut count = 0;
-    let mut max = 0;
-    for (start, end) in ranges {
-        if end <= max {
-            continue;
-        }
-        if start > max {
-            count += end - start + 1;
-            max = end;
-        } else {
-            count += end - max;
-            max = end;
-        }
-    }
-    count.to_string()
+    let (ranges, _) = parse_input(input);
+    ranges.len().to_string()
 }
 
-fn parse_input(input: &Vec<&str>) -> (Vec<(usize, usize)>, Vec<usize>) {
+fn parse_input(input: &Vec<&str>) -> (IntervalSet<usize>, Vec<usize>) {
     let mut it = input.iter();
-    let ranges = it
-        .by_ref()
-        .map_while(|s| {
-            (!s.is_empty()).then(|| {
-                s.split_once('-')
-                    .map(|(a, b)| (a.parse::<usize>().unwrap(), b.parse::<usize>().unwrap()))
-                    .unwrap()
-            })
-        })
-        .collect();
+    let mut ranges = IntervalSet::new();
+    for s in it.by_ref().take_while(|s| !s.is_empty()) {
+        let (a, b) = s.split_once('-').unwrap();
+        ranges.insert(Interval::inclusive(a.parse().unwrap(), b.parse().unwrap()));
+    }
 
     let ids = it.map(|s| s.parse::<usize>().unwrap()).collect();
     (ranges, ids)
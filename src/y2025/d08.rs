@@ -1,6 +1,6 @@
 //! Day 8: Playground
 
-use std::collections::{BinaryHeap, HashMap};
+use crate::utils::union_find::UnionFind;
 
 /// product of sizes of 3 largest circuits of 1000 (10) shortest connections
 pub fn a(input: &Vec<&str>) -> String {
@@ -13,17 +13,7 @@ pub fn a(input: &Vec<&str>) -> String {
     let (circuits, _) = build_circuits(&points, MAX_CONN);
 
     // find 3 largest circuits
-    let mut freq = HashMap::new();
-    for &circuit in &circuits {
-        *freq.entry(circuit).or_insert(0usize) += 1;
-    }
-    let mut freq_vec = freq.values().cloned().collect::<Vec<_>>();
-    freq_vec.sort_unstable_by_key(|count| std::cmp::Reverse(*count));    
-    freq_vec
-        .iter()
-        .take(3)
-        .product::<usize>()
-        .to_string()
+    circuits.component_sizes().iter().take(3).product::<usize>().to_string()
 }
 
 /// product of x coords of last connection to build full circuit
@@ -50,43 +40,26 @@ fn parse_input(input: &Vec<&str>) -> Points {
         .collect()
 }
 
-fn build_circuits(points: &Points, max_conn: usize) -> (Circuits, Option<(usize, usize)>) {
+fn build_circuits(points: &Points, max_conn: usize) -> (UnionFind, Option<(usize, usize)>) {
     let n = points.len();
-    let mut connections = BinaryHeap::with_capacity(n * (n - 1) / 2);
+    let mut connections = Vec::with_capacity(n * (n - 1) / 2);
     for i in 0..n {
         for j in (i + 1)..n {
-            let dist = distance_sq(&points[i], &points[j]);
-            connections.push((std::cmp::Reverse(dist), i, j));
+            connections.push((distance_sq(&points[i], &points[j]), i, j));
         }
     }
+    // only the shortest connections are ever used
+    if max_conn < connections.len() {
+        connections.select_nth_unstable(max_conn);
+        connections.truncate(max_conn);
+    }
+    connections.sort_unstable();
 
     // build circuits from shortest connections
     // (basically Kruskal's algorithm)
-    let mut circuits = (0..n).collect::<Circuits>();
-    let mut count = 0;
-    while count < max_conn && let Some((_, p1, p2)) = connections.pop() {
-        count += 1;
-        let mut c1 = circuits[p1];
-        let mut c2 = circuits[p2];
-        if c1 == c2 {
-            continue;
-        }
-
-        // merge circuits
-        if c1 > c2 {
-            std::mem::swap(&mut c1, &mut c2);
-        }
-        let mut size = 0;
-        for c in circuits.iter_mut() {
-            if *c == c2 {
-                *c = c1;
-                size += 1;
-            } else if *c == c1 {
-                size += 1;
-            }
-        }
-
-        if size == n {
+    let mut circuits = UnionFind::new(n);
+    for (_, p1, p2) in connections {
+        if circuits.union(p1, p2) && circuits.component_count() == 1 {
             return (circuits, Some((p1, p2)));
         }
     }
@@ -108,7 +81,6 @@ struct Point {
     z: isize,
 }
 type Points = Vec<Point>;
-type Circuits = Vec<usize>;
 
 #[test]
 pub fn test() {
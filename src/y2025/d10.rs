@@ -1,19 +1,20 @@
 //! Day 10: Factory
 
+use crate::utils::parallel::par_map;
 use std::collections::{HashSet, VecDeque};
 
 /// fewest button presses to configure indicator lights
 pub fn a(input: &Vec<&str>) -> String {
-    input.iter()
-        .map(|line| Machine::from_str(line).min_presses_for_lights())
+    par_map(input, |line| Machine::from_str(line).min_presses_for_lights())
+        .iter()
         .sum::<usize>()
         .to_string()
 }
 
 /// fewest button presses to configure joltage levels
 pub fn b(input: &Vec<&str>) -> String {
-    input.iter()
-        .map(|line| Machine::from_str(line).min_presses_for_joltage())
+    par_map(input, |line| Machine::from_str(line).min_presses_for_joltage())
+        .iter()
         .sum::<usize>()
         .to_string()
 }
@@ -84,22 +85,22 @@ impl Machine {
         // col - button variable, plus one for the constants (target joltages)
         let num_vars = self.buttons.len();
         let num_eqs = self.joltage.len();
-        let mut matrix = vec![vec![0.0; num_vars + 1]; num_eqs];
+        let mut matrix = vec![vec![0; num_vars + 1]; num_eqs];
 
         for (j, button) in self.buttons.iter().enumerate() {
             for &idx in button {
                 if idx < num_eqs {
-                    matrix[idx][j] = 1.0;
+                    matrix[idx][j] = 1;
                 }
             }
         }
 
         for (i, &val) in self.joltage.iter().enumerate() {
-            matrix[i][num_vars] = val as f64;
+            matrix[i][num_vars] = val as i128;
         }
 
         // Solve the system minimizing the sum of button presses (variables)
-        crate::utils::solve_min_integer_sum(matrix)
+        crate::utils::linalg::solve_min_integer_sum(&matrix).unwrap() as usize
     }
 }
 
@@ -113,4 +114,7 @@ pub fn test() {
 
     assert_eq!(a(&input), "7");
     assert_eq!(b(&input), "33");
+
+    // both free buttons have positive and negative coefficients after reduction
+    assert_eq!(b(&vec!["[.#.] (1,2) (0,2) (0) (1) (2) {6,4,2}"]), "10");
 }
@@ -1,6 +1,6 @@
 //! Day 11: Reactor
 
-use std::collections::{HashMap, HashSet};
+use crate::{dot, utils::graph::Graph};
 
 /// Number of unique paths from `you` to `out`
 pub fn a(input: &Vec<&str>) -> String {
@@ -23,41 +23,37 @@ pub fn b(input: &Vec<&str>) -> String {
     (count_paths(&graph, "svr", u) * n * count_paths(&graph, v, "out")).to_string()
 }
 
-fn parse_input<'a>(input: &[&'a str]) -> HashMap<&'a str, Vec<&'a str>> {
-    input
-        .iter()
-        .map(|s| (&s[0..3], s[5..].split_whitespace().collect()))
-        .collect()
+fn parse_input<'a>(input: &[&'a str]) -> Graph<&'a str> {
+    let mut graph = Graph::directed();
+    for s in input {
+        for v in s[5..].split_whitespace() {
+            graph.edge(&s[0..3], v);
+        }
+    }
+    dot::export(|| {
+        let mut dot = dot::Graph::directed("devices");
+        for s in input {
+            for v in s[5..].split_whitespace() {
+                dot.edge(&s[0..3], v);
+            }
+        }
+        // only devices of the input, part a lacks those of part b
+        for id in ["you", "svr", "out", "dac", "fft"] {
+            if graph.index(id).is_some() {
+                dot.node(id).kind("marker");
+            }
+        }
+        dot
+    });
+    graph
 }
 
 /// Count unique paths through DAG from `start` to `end`
-fn count_paths(graph: &HashMap<&str, Vec<&str>>, start: &str, end: &str) -> usize {
-    let mut counts = HashMap::new();
-    let mut visited = HashSet::new();
-    let mut stack = vec![(start, false)];
-
-    while let Some((u, processed)) = stack.pop() {
-        if processed {
-            let count = if u == end {
-                1
-            } else {
-                graph
-                    .get(u)
-                    .into_iter()
-                    .flatten()
-                    .map(|v| counts.get(v).copied().unwrap_or(0))
-                    .sum()
-            };
-            counts.insert(u, count);
-        } else if visited.insert(u) {
-            stack.push((u, true));
-            if let Some(neighbors) = graph.get(u) {
-                stack.extend(neighbors.iter().map(|&v| (v, false)));
-            }
-        }
+fn count_paths(graph: &Graph<&str>, start: &str, end: &str) -> u64 {
+    match (graph.index(start), graph.index(end)) {
+        (Some(start), Some(end)) => graph.count_paths(start, end),
+        _ => 0,
     }
-
-    *counts.get(start).unwrap()
 }
 
 #[test]